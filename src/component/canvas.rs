@@ -0,0 +1,197 @@
+use crate::model::{GeoJsonLayer, Px, Shape, ShapeCache, Style, Viewport};
+use std::f64::consts::PI;
+use std::rc::Rc;
+use stdweb::js;
+use stdweb::unstable::TryInto;
+use stdweb::web::html_element::CanvasElement;
+use stdweb::web::{CanvasRenderingContext2d, FillRule, LineCap, LineJoin};
+use yew::services::render::{RenderService, RenderTask};
+use yew::{html, Component, ComponentLink, Html, NodeRef, Properties, ShouldRender};
+
+/// Vector layer drawn on canvas on top of tile grid
+pub struct Canvas {
+    link: ComponentLink<Self>,
+    // visible viewport
+    vw: Viewport,
+    // viewport shapes are clipped to
+    vw_outer: Viewport,
+    layer: GeoJsonLayer,
+    // projected shapes per zoom level
+    cache: ShapeCache,
+    // shapes on current zoom level
+    shapes: Rc<Vec<Vec<Shape>>>,
+    canvas: NodeRef,
+    // render service vars
+    render: RenderService,
+    render_task: Option<RenderTask>,
+}
+
+pub enum Msg {
+    Draw,
+}
+
+#[derive(Properties, PartialEq, Clone, Default)]
+pub struct Prop {
+    pub vw: Viewport,
+    pub vw_outer: Viewport,
+    pub layer: GeoJsonLayer,
+}
+
+impl Canvas {
+    // draws on next animation frame, pending draw is reused
+    fn schedule(&mut self) {
+        if self.render_task.is_none() {
+            let cb = self.link.send_back(|_| Msg::Draw);
+            self.render_task = Some(self.render.request_animation_frame(cb));
+        }
+    }
+
+    fn draw(&self) {
+        if let Some(ctx) = context(&self.canvas, &self.vw.size()) {
+            // context style is set once per feature
+            let mut current = None;
+            for (i, s) in self
+                .layer
+                .view_shapes(&self.shapes, &self.vw, &self.vw_outer)
+            {
+                let style = self.layer.feature_style(i, self.vw.z);
+                if current != Some(i) {
+                    set_style(&ctx, &style);
+                    current = Some(i);
+                }
+                shape(&ctx, &s, &style);
+            }
+        }
+    }
+}
+
+impl Component for Canvas {
+    type Message = Msg;
+    type Properties = Prop;
+
+    fn create(prop: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut cache = ShapeCache::default();
+        Canvas {
+            link,
+            shapes: cache.shapes(&prop.layer, prop.vw.z),
+            vw: prop.vw,
+            vw_outer: prop.vw_outer,
+            layer: prop.layer,
+            cache,
+            canvas: NodeRef::default(),
+            render: RenderService::new(),
+            render_task: None,
+        }
+    }
+
+    fn mounted(&mut self) -> ShouldRender {
+        self.schedule();
+        false
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Draw => {
+                self.render_task = None;
+                self.draw();
+            }
+        }
+        false
+    }
+
+    fn change(&mut self, prop: Self::Properties) -> ShouldRender {
+        // features are compared by reference, data is not expected to change in place
+        let changed = self.vw != prop.vw
+            || self.vw_outer != prop.vw_outer
+            || self.layer.style != prop.layer.style
+            || self.layer.style_spec != prop.layer.style_spec
+            || self.layer.simplify != prop.layer.simplify
+            || !Rc::ptr_eq(&self.layer.features, &prop.layer.features);
+        let resized = self.vw.size() != prop.vw.size();
+        if changed {
+            self.shapes = self.cache.shapes(&prop.layer, prop.vw.z);
+            self.schedule();
+        }
+        self.vw = prop.vw;
+        self.vw_outer = prop.vw_outer;
+        self.layer = prop.layer;
+        // canvas is redrawn directly, element changes only with size
+        resized
+    }
+
+    fn view(&self) -> Html<Self> {
+        let size = self.vw.size();
+        html! {
+            <canvas class="remap-vector-layer", ref=self.canvas.clone(),
+                style=format!("width: {}px; height: {}px", size.x, size.y),></canvas>
+        }
+    }
+}
+
+/// Resizes and clears canvas, returns its 2D context scaled to css pixels
+pub fn context(canvas: &NodeRef, size: &Px) -> Option<CanvasRenderingContext2d> {
+    let canvas = NodeRef::try_into::<CanvasElement>(canvas)?;
+    let ctx: CanvasRenderingContext2d = canvas.get_context().ok()?;
+    // backing store is scaled to device pixels for sharp lines
+    let ratio = device_ratio();
+    // resizing also clears the canvas
+    canvas.set_width((size.x as f64 * ratio).round() as u32);
+    canvas.set_height((size.y as f64 * ratio).round() as u32);
+    ctx.set_transform(ratio, 0.0, 0.0, ratio, 0.0, 0.0);
+    Some(ctx)
+}
+
+/// Returns device pixels per css pixel
+pub fn device_ratio() -> f64 {
+    js! { return window.devicePixelRatio || 1; }
+        .try_into()
+        .unwrap_or(1.0)
+}
+
+/// Sets line and fill style of context
+pub fn set_style(ctx: &CanvasRenderingContext2d, style: &Style) {
+    ctx.set_stroke_style_color(&style.stroke);
+    ctx.set_fill_style_color(&style.fill);
+    ctx.set_line_width(style.stroke_width);
+    ctx.set_line_cap(LineCap::Round);
+    ctx.set_line_join(LineJoin::Round);
+    ctx.set_line_dash(style.dash.clone());
+}
+
+/// Draws projected shape
+pub fn shape(ctx: &CanvasRenderingContext2d, shape: &Shape, style: &Style) {
+    ctx.begin_path();
+    match shape {
+        Shape::Point(p) => {
+            ctx.arc(p.x as f64, p.y as f64, style.radius, 0.0, 2.0 * PI, false);
+            fill(ctx, style);
+        }
+        Shape::Line(line) => path(ctx, line),
+        Shape::Polygon(rings) => {
+            for ring in rings {
+                path(ctx, ring);
+                ctx.close_path();
+            }
+            fill(ctx, style);
+        }
+    }
+    ctx.set_global_alpha(style.stroke_opacity);
+    ctx.stroke();
+}
+
+// fills current path, holes are cut out by the evenodd fill rule
+fn fill(ctx: &CanvasRenderingContext2d, style: &Style) {
+    ctx.set_global_alpha(style.fill_opacity);
+    ctx.fill(FillRule::EvenOdd);
+}
+
+// adds line to current path
+fn path(ctx: &CanvasRenderingContext2d, line: &[Px]) {
+    for (i, p) in line.iter().enumerate() {
+        if i == 0 {
+            ctx.move_to(p.x as f64, p.y as f64);
+        } else {
+            ctx.line_to(p.x as f64, p.y as f64);
+        }
+    }
+}
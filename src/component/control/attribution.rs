@@ -0,0 +1,45 @@
+use super::{Context, Control};
+use crate::component::map::Map;
+use crate::component::raw::RawHtml;
+use yew::{html, Html};
+
+/// Credits of visible layers, separated by '|'
+///
+/// Prefix and layer attributions are inserted as raw html.
+pub struct AttributionControl {
+    /// Html shown before layer attributions
+    pub prefix: Option<String>,
+    // credits node, kept while credits are unchanged
+    html: RawHtml,
+}
+
+impl AttributionControl {
+    pub fn new(prefix: Option<String>) -> Self {
+        Self {
+            prefix,
+            html: RawHtml::default(),
+        }
+    }
+}
+
+impl Default for AttributionControl {
+    fn default() -> Self {
+        Self::new(Some("Remap".into()))
+    }
+}
+
+impl Control for AttributionControl {
+    fn view(&self, ctx: &Context) -> Html<Map> {
+        let parts: Vec<&str> = self
+            .prefix
+            .iter()
+            .chain(ctx.attributions.iter())
+            .map(|s| s.as_str())
+            .collect();
+        if parts.is_empty() {
+            return html! {};
+        }
+        self.html
+            .view(0, "remap-control-attribution", &parts.join(" | "))
+    }
+}
@@ -0,0 +1,123 @@
+use super::{Context, Control};
+use crate::component::map::Map;
+use crate::model::{CoordFormat, LonLat};
+use stdweb::js;
+use yew::{html, Component, ComponentLink, Html, Properties, ShouldRender};
+
+/// Coordinates under mouse pointer, click copies them to clipboard
+pub struct CoordinatesControl {
+    /// Formats to switch between, first is shown initially
+    pub formats: Vec<CoordFormat>,
+}
+
+impl Default for CoordinatesControl {
+    fn default() -> Self {
+        Self {
+            formats: vec![CoordFormat::Decimal, CoordFormat::Dms, CoordFormat::Meters],
+        }
+    }
+}
+
+impl Control for CoordinatesControl {
+    fn view(&self, ctx: &Context) -> Html<Map> {
+        html! {
+            <Readout: cursor=ctx.cursor, formats=self.formats.clone(), />
+        }
+    }
+
+    fn tracks_cursor(&self) -> bool {
+        true
+    }
+}
+
+/// Readout holding selected format
+pub struct Readout {
+    cursor: Option<LonLat>,
+    formats: Vec<CoordFormat>,
+    // index of selected format
+    selected: usize,
+    // shown until cursor moves
+    copied: bool,
+}
+
+pub enum Msg {
+    NextFormat,
+    Copy,
+}
+
+#[derive(Properties, PartialEq, Clone, Default)]
+pub struct Prop {
+    pub cursor: Option<LonLat>,
+    pub formats: Vec<CoordFormat>,
+}
+
+impl Readout {
+    fn format(&self) -> CoordFormat {
+        self.formats.get(self.selected).cloned().unwrap_or_default()
+    }
+}
+
+impl Component for Readout {
+    type Message = Msg;
+    type Properties = Prop;
+
+    fn create(prop: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Readout {
+            cursor: prop.cursor,
+            formats: prop.formats,
+            selected: 0,
+            copied: false,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::NextFormat => {
+                self.selected = (self.selected + 1) % self.formats.len().max(1);
+            }
+            Msg::Copy => {
+                let text = match self.cursor {
+                    Some(ll) => self.format().format(&ll),
+                    None => return false,
+                };
+                js! { @(no_return)
+                    if (navigator.clipboard) {
+                        navigator.clipboard.writeText(@{text});
+                    }
+                }
+                self.copied = true;
+            }
+        }
+        true
+    }
+
+    fn change(&mut self, prop: Self::Properties) -> ShouldRender {
+        if self.formats != prop.formats {
+            self.selected = 0;
+        }
+        let changed = self.cursor != prop.cursor || self.formats != prop.formats;
+        if changed {
+            self.copied = false;
+        }
+        self.cursor = prop.cursor;
+        self.formats = prop.formats;
+        changed
+    }
+
+    fn view(&self) -> Html<Self> {
+        let format = self.format();
+        let text = self.cursor.map(|ll| format.format(&ll)).unwrap_or_default();
+        let class = if self.copied {
+            "remap-control-coordinates-value remap-control-copied"
+        } else {
+            "remap-control-coordinates-value"
+        };
+        html! {
+            <div class="remap-control-coordinates",>
+                <span class=class, title="Copy", onclick=|_| Msg::Copy,>{ text }</span>
+                <button class="remap-control-coordinates-format", title="Change format",
+                    onclick=|_| Msg::NextFormat,>{ format.name() }</button>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use super::{Action, Context, Control};
+use crate::component::map::{Map, Msg};
+use yew::{html, Html};
+
+/// Button toggling fullscreen of map element
+pub struct FullscreenControl;
+
+impl Control for FullscreenControl {
+    fn view(&self, ctx: &Context) -> Html<Map> {
+        let (class, title) = if ctx.fullscreen {
+            (
+                "remap-control remap-control-fullscreen remap-control-active",
+                "Exit fullscreen",
+            )
+        } else {
+            ("remap-control remap-control-fullscreen", "Fullscreen")
+        };
+        html! {
+            <i class=class, title=title, onclick=|_| Msg::Action(Action::ToggleFullscreen),></i>
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use super::{Action, Context, Control};
+use crate::component::map::{Map, Msg};
+use crate::model::TileLayer;
+use yew::{html, Html};
+
+/// Tile layer list, base layers as radio buttons and overlays as checkboxes
+#[derive(Default)]
+pub struct LayerSwitcher;
+
+// unnamed layers are listed by index
+fn name(idx: usize, layer: &TileLayer) -> String {
+    if layer.name.is_empty() {
+        format!("Layer {}", idx + 1)
+    } else {
+        layer.name.clone()
+    }
+}
+
+// radio button or checkbox of layer
+fn item(ctx: &Context, idx: usize, layer: &TileLayer) -> Html<Map> {
+    let input = if layer.overlay {
+        html! {
+            <input type="checkbox", checked=layer.visible,
+                onclick=|_| Msg::Action(Action::ToggleLayer(idx)), />
+        }
+    } else {
+        html! {
+            // base layers of each map form their own radio group
+            <input type="radio", name=format!("{}-base-layer", ctx.id), checked=layer.visible,
+                onclick=|_| Msg::Action(Action::SelectBaseLayer(idx)), />
+        }
+    };
+    html! {
+        <label class="remap-control-layer",>
+            { input }
+            { name(idx, layer) }
+        </label>
+    }
+}
+
+impl Control for LayerSwitcher {
+    fn view(&self, ctx: &Context) -> Html<Map> {
+        let layers = || ctx.layers.iter().enumerate();
+        let separator = if layers().any(|(_, l)| l.overlay) && layers().any(|(_, l)| !l.overlay) {
+            html! { <hr class="remap-control-layers-separator",/> }
+        } else {
+            html! {}
+        };
+        html! {
+            <div class="remap-control-layers",>
+                { for layers().filter(|(_, l)| !l.overlay).map(|(idx, l)| item(ctx, idx, l)) }
+                { separator }
+                { for layers().filter(|(_, l)| l.overlay).map(|(idx, l)| item(ctx, idx, l)) }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use super::{Action, Context, Control};
+use crate::component::map::{Map, Msg};
+use yew::{html, Html};
+
+/// Button centering map on user position, pressed again to stop
+#[derive(Default)]
+pub struct LocateControl {
+    /// Keep map centered on position updates
+    pub follow: bool,
+}
+
+impl Control for LocateControl {
+    fn view(&self, ctx: &Context) -> Html<Map> {
+        let class = if ctx.locating {
+            "remap-control remap-control-locate remap-control-active"
+        } else {
+            "remap-control remap-control-locate"
+        };
+        let follow = self.follow;
+        html! {
+            <i class=class, title="Show my location",
+                onclick=|_| Msg::Action(Action::ToggleLocate(follow)),></i>
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use super::{Action, Context, Control};
+use crate::component::map::{Map, Msg};
+use crate::model::MeasureKind;
+use yew::{html, Html};
+
+/// Distance and area measuring buttons, double click finishes measurement
+pub struct MeasureControl;
+
+impl Control for MeasureControl {
+    fn view(&self, ctx: &Context) -> Html<Map> {
+        let class = |name: &str, kind: MeasureKind| {
+            if ctx.measuring == Some(kind) {
+                format!("remap-control {} remap-control-active", name)
+            } else {
+                format!("remap-control {}", name)
+            }
+        };
+        html! {
+            <div class="remap-control-measure",>
+                <i class=class("remap-control-measure-distance", MeasureKind::Distance),
+                    title="Measure distance",
+                    onclick=|_| Msg::Action(Action::ToggleMeasure(MeasureKind::Distance)),></i>
+                <i class=class("remap-control-measure-area", MeasureKind::Area),
+                    title="Measure area",
+                    onclick=|_| Msg::Action(Action::ToggleMeasure(MeasureKind::Area)),></i>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use super::{Action, Context, Control};
+use crate::component::map::{Map, Msg as MapMsg};
+use crate::component::Grid;
+use crate::model::{LonLat, Px, TileLayer, Viewport};
+use yew::events::IMouseEvent;
+use yew::{html, Callback, Component, ComponentLink, Html, Properties, ShouldRender};
+
+/// Overview map at lower zoom level, main view is panned live by dragging its rectangle
+/// with mouse or touch
+pub struct MinimapControl {
+    /// Zoom levels below main map
+    pub zoom_offset: usize,
+    /// Size in pixels
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Default for MinimapControl {
+    fn default() -> Self {
+        Self {
+            zoom_offset: 5,
+            width: 150,
+            height: 150,
+        }
+    }
+}
+
+impl Control for MinimapControl {
+    fn view(&self, ctx: &Context) -> Html<Map> {
+        // visible base layers, overlays are left out at overview scale
+        let layers: Vec<TileLayer> = ctx
+            .layers
+            .iter()
+            .filter(|l| l.visible && !l.overlay)
+            .cloned()
+            .collect();
+        html! {
+            <Minimap: main=ctx.vw, layers=layers, zoom_offset=self.zoom_offset,
+                size=(self.width, self.height),
+                onpan=|ll| MapMsg::Action(Action::CenterOn(ll)), />
+        }
+    }
+}
+
+/// Minimap following main map viewport
+pub struct Minimap {
+    // main map viewport
+    main: Viewport,
+    layers: Vec<TileLayer>,
+    zoom_offset: usize,
+    size: (i32, i32),
+    onpan: Option<Callback<LonLat>>,
+    // last screen position of rectangle drag and offset panned but not yet shown by main map
+    drag: Option<(Px, Px)>,
+}
+
+pub enum Msg {
+    DragBegin(i64, i64),
+    Drag(i64, i64),
+    DragEnd,
+}
+
+#[derive(Properties, PartialEq, Clone, Default)]
+pub struct Prop {
+    pub main: Viewport,
+    pub layers: Vec<TileLayer>,
+    pub zoom_offset: usize,
+    pub size: (i32, i32),
+    pub onpan: Option<Callback<LonLat>>,
+}
+
+impl Minimap {
+    // minimap viewport centered on main map
+    fn viewport(&self) -> Viewport {
+        let z = self.main.z.saturating_sub(self.zoom_offset);
+        Viewport::new(&self.main.center(), self.size, z)
+    }
+}
+
+impl Component for Minimap {
+    type Message = Msg;
+    type Properties = Prop;
+
+    fn create(prop: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Minimap {
+            main: prop.main,
+            layers: prop.layers,
+            zoom_offset: prop.zoom_offset,
+            size: prop.size,
+            onpan: prop.onpan,
+            drag: None,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::DragBegin(x, y) => {
+                self.drag = Some(((x, y).into(), Px::default()));
+                false
+            }
+            Msg::Drag(x, y) => match self.drag {
+                Some((last, pending)) => {
+                    let pos = Px::from((x, y));
+                    if pos == last {
+                        return false;
+                    }
+                    let pending = pending.translate(&pos.distance(&last));
+                    self.drag = Some((pos, pending));
+                    // main map follows the dragged rectangle center
+                    let vw = self.viewport();
+                    let center = self.main.center().px(vw.z).translate(&pending).lonlat(vw.z);
+                    if let Some(ref cb) = self.onpan {
+                        cb.emit(center);
+                    }
+                    true
+                }
+                None => false,
+            },
+            // main map is already panned while dragging
+            Msg::DragEnd => self.drag.take().is_some(),
+        }
+    }
+
+    fn change(&mut self, prop: Self::Properties) -> ShouldRender {
+        // main map redraws minimap on every change, so props are compared here
+        let changed = self.main != prop.main
+            || self.layers != prop.layers
+            || self.zoom_offset != prop.zoom_offset
+            || self.size != prop.size;
+        // panned offset is now part of main viewport
+        if self.main != prop.main {
+            if let Some((last, _)) = self.drag {
+                self.drag = Some((last, Px::default()));
+            }
+        }
+        self.main = prop.main;
+        self.layers = prop.layers;
+        self.zoom_offset = prop.zoom_offset;
+        self.size = prop.size;
+        self.onpan = prop.onpan;
+        changed
+    }
+
+    fn view(&self) -> Html<Self> {
+        let vw = self.viewport();
+        let nw = vw.point_offset(&(self.main.lon_min, self.main.lat_max).into());
+        let se = vw.point_offset(&(self.main.lon_max, self.main.lat_min).into());
+        let offset = self.drag.map(|(_, offset)| offset).unwrap_or_default();
+        let rect = format!(
+            "transform: translate({}px, {}px); width: {}px; height: {}px",
+            nw.x + offset.x,
+            nw.y + offset.y,
+            (se.x - nw.x).max(4),
+            (se.y - nw.y).max(4)
+        );
+        html! {
+            <div class="remap-control-minimap",
+                style=format!("width: {}px; height: {}px", self.size.0, self.size.1),
+                // pointer events cover both mouse and touch
+                onpointermove=|e| Msg::Drag(e.screen_x() as i64, e.screen_y() as i64),
+                onpointerup=|_| Msg::DragEnd,
+                onpointercancel=|_| Msg::DragEnd,
+                onpointerleave=|_| Msg::DragEnd,>
+                <Grid: vw=vw, vw_outer=vw, layers=self.layers.clone(), hidpi=false, />
+                <div class="remap-control-minimap-rect", style=rect,
+                    onpointerdown=|e| Msg::DragBegin(e.screen_x() as i64, e.screen_y() as i64),></div>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,171 @@
+mod attribution;
+mod coordinates;
+mod fullscreen;
+mod layers;
+mod locate;
+mod measure;
+mod minimap;
+mod scale;
+mod zoom;
+
+pub use self::attribution::AttributionControl;
+pub use self::coordinates::CoordinatesControl;
+pub use self::fullscreen::FullscreenControl;
+pub use self::layers::LayerSwitcher;
+pub use self::locate::LocateControl;
+pub use self::measure::MeasureControl;
+pub use self::minimap::MinimapControl;
+pub use self::scale::ScaleControl;
+pub use self::zoom::ZoomControl;
+
+use super::map::Map;
+use crate::model::{LonLat, MeasureKind, TileLayer, Viewport};
+use std::rc::Rc;
+use yew::{html, Html};
+
+/// Map corner controls are placed in
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Corner {
+    /// All corners in drawing order
+    pub const ALL: [Corner; 4] = [
+        Corner::TopLeft,
+        Corner::TopRight,
+        Corner::BottomLeft,
+        Corner::BottomRight,
+    ];
+
+    // css class of corner container
+    fn class(self) -> &'static str {
+        match self {
+            Corner::TopLeft => "remap-controls remap-controls-top-left",
+            Corner::TopRight => "remap-controls remap-controls-top-right",
+            Corner::BottomLeft => "remap-controls remap-controls-bottom-left",
+            Corner::BottomRight => "remap-controls remap-controls-bottom-right",
+        }
+    }
+}
+
+/// Map state given to controls on render
+pub struct Context<'a> {
+    /// Id of map element, unique per map
+    pub id: &'a str,
+    /// Visible viewport, panning included
+    pub vw: Viewport,
+    pub zoom: usize,
+    pub min_zoom: usize,
+    pub max_zoom: usize,
+    /// Raster tile layers in drawing order
+    pub layers: &'a [TileLayer],
+    /// Attributions of visible layers without duplicates
+    pub attributions: &'a [String],
+    /// Last position of mouse pointer on map
+    pub cursor: Option<LonLat>,
+    pub fullscreen: bool,
+    /// Map is locating or showing user position
+    pub locating: bool,
+    /// Kind of active measurement
+    pub measuring: Option<MeasureKind>,
+}
+
+/// Map operations requested by controls, sent as `Msg::Action`
+pub enum Action {
+    ZoomIn,
+    ZoomOut,
+    /// Shows base tile layer by index, hiding other base layers
+    SelectBaseLayer(usize),
+    /// Shows or hides overlay tile layer by index
+    ToggleLayer(usize),
+    /// Pans map to center on position
+    CenterOn(LonLat),
+    /// Enters or leaves fullscreen
+    ToggleFullscreen,
+    /// Starts or stops locating user, map follows position updates if set
+    ToggleLocate(bool),
+    /// Starts measuring, or stops if measuring the same kind
+    ToggleMeasure(MeasureKind),
+}
+
+/// Map control drawn on top of map
+///
+/// Control html is rendered by map, so its callbacks send map messages.
+pub trait Control {
+    fn view(&self, ctx: &Context) -> Html<Map>;
+
+    /// Whether control is redrawn whenever mouse moves on map
+    fn tracks_cursor(&self) -> bool {
+        false
+    }
+}
+
+/// Control with its placement on map
+#[derive(Clone)]
+pub struct MapControl {
+    pub control: Rc<dyn Control>,
+    pub corner: Corner,
+    /// Controls in a corner are stacked by ascending order, from map edge inwards
+    pub order: i32,
+    /// Disabled controls are not drawn
+    pub enabled: bool,
+}
+
+// controls are equal if they share the control instance
+impl PartialEq for MapControl {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.control, &other.control)
+            && self.corner == other.corner
+            && self.order == other.order
+            && self.enabled == other.enabled
+    }
+}
+
+impl MapControl {
+    /// Creates enabled control in corner
+    pub fn new<C: Control + 'static>(control: C, corner: Corner) -> Self {
+        Self {
+            control: Rc::new(control),
+            corner,
+            order: 0,
+            enabled: true,
+        }
+    }
+
+    /// Returns default map controls
+    pub fn defaults() -> Vec<MapControl> {
+        vec![
+            MapControl::new(ZoomControl, Corner::TopLeft),
+            MapControl::new(AttributionControl::default(), Corner::BottomRight),
+        ]
+    }
+}
+
+/// Draws enabled controls grouped by corner
+pub fn view(controls: &[MapControl], ctx: &Context) -> Html<Map> {
+    let view_corner = |corner: Corner| {
+        let mut controls: Vec<&MapControl> = controls
+            .iter()
+            .filter(|c| c.enabled && c.corner == corner)
+            .collect();
+        if controls.is_empty() {
+            return html! {};
+        }
+        // stable sort keeps given order for equal orders
+        controls.sort_by_key(|c| c.order);
+        html! {
+            <div class=corner.class(),>
+                { for controls.iter().map(|c| c.control.view(ctx)) }
+            </div>
+        }
+    };
+    html! {
+        <>
+            { for Corner::ALL.iter().map(|&corner| view_corner(corner)) }
+        </>
+    }
+}
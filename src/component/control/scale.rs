@@ -0,0 +1,47 @@
+use super::{Context, Control};
+use crate::component::map::Map;
+use crate::model::{ScaleBar, Units};
+use yew::{html, Html};
+
+/// Scale bar of round distance at map center, follows panning since scale depends on latitude
+pub struct ScaleControl {
+    pub units: Units,
+    /// Maximum bar width in pixels
+    pub max_width: f64,
+}
+
+impl Default for ScaleControl {
+    fn default() -> Self {
+        Self {
+            units: Units::default(),
+            max_width: 100.0,
+        }
+    }
+}
+
+impl Control for ScaleControl {
+    fn view(&self, ctx: &Context) -> Html<Map> {
+        let mpp = ctx.vw.center().meters_per_pixel(ctx.zoom);
+        let bar = |bar: ScaleBar| {
+            html! {
+                <div class="remap-control-scale-bar", style=format!("width: {}px", bar.width.round()),>
+                    { bar.label }
+                </div>
+            }
+        };
+        let metric = match self.units {
+            Units::Metric | Units::Both => bar(ScaleBar::metric(mpp, self.max_width)),
+            Units::Imperial => html! {},
+        };
+        let imperial = match self.units {
+            Units::Imperial | Units::Both => bar(ScaleBar::imperial(mpp, self.max_width)),
+            Units::Metric => html! {},
+        };
+        html! {
+            <div class="remap-control-scale",>
+                { metric }
+                { imperial }
+            </div>
+        }
+    }
+}
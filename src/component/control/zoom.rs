@@ -0,0 +1,26 @@
+use super::{Action, Context, Control};
+use crate::component::map::{Map, Msg};
+use yew::{html, Html};
+
+/// Zoom in and out buttons, disabled at zoom bounds
+pub struct ZoomControl;
+
+impl Control for ZoomControl {
+    fn view(&self, ctx: &Context) -> Html<Map> {
+        let class = |name: &str, disabled: bool| {
+            if disabled {
+                format!("remap-control {} remap-control-disabled", name)
+            } else {
+                format!("remap-control {}", name)
+            }
+        };
+        html! {
+            <div class="remap-control-zoom",>
+                <i class=class("remap-control-zoom-in", ctx.zoom >= ctx.max_zoom),
+                    onclick=|_| Msg::Action(Action::ZoomIn),></i>
+                <i class=class("remap-control-zoom-out", ctx.zoom <= ctx.min_zoom),
+                    onclick=|_| Msg::Action(Action::ZoomOut),></i>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::model::{GeoJsonLayer, Px, Shape, ShapeCache, Style, Viewport};
+use std::rc::Rc;
+use yew::{html, Component, ComponentLink, Html, Properties, ShouldRender};
+
+/// GeoJSON layer drawn as svg on top of tile grid
+pub struct GeoJson {
+    // visible viewport
+    vw: Viewport,
+    // viewport shapes are clipped to
+    vw_outer: Viewport,
+    layer: GeoJsonLayer,
+    // projected shapes per zoom level
+    cache: ShapeCache,
+    // shapes on current zoom level
+    shapes: Rc<Vec<Vec<Shape>>>,
+}
+
+pub enum Msg {}
+
+#[derive(Properties, PartialEq, Clone, Default)]
+pub struct Prop {
+    pub vw: Viewport,
+    pub vw_outer: Viewport,
+    pub layer: GeoJsonLayer,
+}
+
+impl Component for GeoJson {
+    type Message = Msg;
+    type Properties = Prop;
+
+    fn create(prop: Self::Properties, _: ComponentLink<Self>) -> Self {
+        let mut cache = ShapeCache::default();
+        GeoJson {
+            shapes: cache.shapes(&prop.layer, prop.vw.z),
+            vw: prop.vw,
+            vw_outer: prop.vw_outer,
+            layer: prop.layer,
+            cache,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {}
+    }
+
+    fn change(&mut self, prop: Self::Properties) -> ShouldRender {
+        // features are compared by reference, data is not expected to change in place
+        let changed = self.vw != prop.vw
+            || self.vw_outer != prop.vw_outer
+            || self.layer.style != prop.layer.style
+            || self.layer.style_spec != prop.layer.style_spec
+            || self.layer.simplify != prop.layer.simplify
+            || !Rc::ptr_eq(&self.layer.features, &prop.layer.features);
+        if changed {
+            self.shapes = self.cache.shapes(&prop.layer, prop.vw.z);
+        }
+        self.vw = prop.vw;
+        self.vw_outer = prop.vw_outer;
+        self.layer = prop.layer;
+        changed
+    }
+
+    fn view(&self) -> Html<Self> {
+        let size = self.vw.size();
+        let layer = &self.layer;
+        let z = self.vw.z;
+        let shapes = self
+            .layer
+            .view_shapes(&self.shapes, &self.vw, &self.vw_outer);
+
+        html! {
+            <svg class="remap-vector-layer", width=size.x, height=size.y,>
+                { for shapes.map(|(i, s)| shape(&s, &layer.feature_style(i, z))) }
+            </svg>
+        }
+    }
+}
+
+// draws projected shape as svg element
+fn shape(shape: &Shape, style: &Style) -> Html<GeoJson> {
+    let dash = style
+        .dash
+        .iter()
+        .map(|d| d.to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+    match shape {
+        Shape::Point(p) => html! {
+            <circle cx=p.x, cy=p.y, r=style.radius,
+                stroke=&style.stroke, stroke-width=style.stroke_width,
+                stroke-opacity=style.stroke_opacity,
+                fill=&style.fill, fill-opacity=style.fill_opacity, />
+        },
+        Shape::Line(line) => html! {
+            <path d=path(&[line], false), fill="none",
+                stroke=&style.stroke, stroke-width=style.stroke_width,
+                stroke-opacity=style.stroke_opacity, stroke-dasharray=dash,
+                stroke-linecap="round", stroke-linejoin="round", />
+        },
+        Shape::Polygon(rings) => html! {
+            // holes are cut out by the evenodd fill rule
+            <path d=path(rings, true), fill-rule="evenodd",
+                stroke=&style.stroke, stroke-width=style.stroke_width,
+                stroke-opacity=style.stroke_opacity, stroke-dasharray=dash,
+                stroke-linejoin="round",
+                fill=&style.fill, fill-opacity=style.fill_opacity, />
+        },
+    }
+}
+
+// builds svg path data, rings are closed if `close` is set
+fn path<L: AsRef<[Px]>>(lines: &[L], close: bool) -> String {
+    let mut d = String::new();
+    for line in lines {
+        for (i, p) in line.as_ref().iter().enumerate() {
+            let cmd = if i == 0 { 'M' } else { 'L' };
+            d.push_str(&format!("{}{} {}", cmd, p.x, p.y));
+        }
+        if close && !line.as_ref().is_empty() {
+            d.push('Z');
+        }
+    }
+    d
+}
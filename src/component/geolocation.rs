@@ -0,0 +1,236 @@
+use crate::model::{GeolocationError, Location};
+use crate::state::locate::{State, Update};
+use std::cell::Cell;
+use std::rc::Rc;
+use stdweb::js;
+use stdweb::Value;
+use yew::services::Task;
+use yew::Callback;
+
+/// Position source of geolocation control
+pub trait GeolocationProvider {
+    /// Starts watching position, results are sent until returned task is dropped
+    fn watch(&self, callback: Callback<Result<Location, GeolocationError>>) -> Box<dyn Task>;
+}
+
+/// Browser Geolocation API
+#[derive(Default)]
+pub struct BrowserGeolocation {
+    /// Maximum wait for a position in milliseconds
+    pub timeout: u32,
+}
+
+impl GeolocationProvider for BrowserGeolocation {
+    fn watch(&self, callback: Callback<Result<Location, GeolocationError>>) -> Box<dyn Task> {
+        let cb = callback.clone();
+        let found = move |lon: f64, lat: f64, accuracy: f64| {
+            cb.emit(Ok(Location {
+                position: (lon, lat).into(),
+                accuracy,
+            }))
+        };
+        let cb = callback.clone();
+        let failed = move |code: u32| cb.emit(Err(GeolocationError::from_code(code)));
+        // zero timeout waits forever
+        let timeout = if self.timeout > 0 {
+            f64::from(self.timeout)
+        } else {
+            std::f64::INFINITY
+        };
+        let handle = js! {
+            var found = @{found};
+            var failed = @{failed};
+            if (!navigator.geolocation) {
+                found.drop();
+                failed.drop();
+                return null;
+            }
+            var id = navigator.geolocation.watchPosition(
+                function(p) { found(p.coords.longitude, p.coords.latitude, p.coords.accuracy); },
+                function(e) { failed(e.code); },
+                { enableHighAccuracy: true, timeout: @{timeout} }
+            );
+            return { id: id, found: found, failed: failed };
+        };
+        if let Value::Null = handle {
+            callback.emit(Err(GeolocationError::Unsupported));
+            return Box::new(WatchTask(None));
+        }
+        Box::new(WatchTask(Some(handle)))
+    }
+}
+
+// browser position watch and its callbacks
+struct WatchTask(Option<Value>);
+
+impl Task for WatchTask {
+    fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn cancel(&mut self) {
+        if let Some(handle) = self.0.take() {
+            js! { @(no_return)
+                var handle = @{handle};
+                navigator.geolocation.clearWatch(handle.id);
+                handle.found.drop();
+                handle.failed.drop();
+            }
+        }
+    }
+}
+
+impl Drop for WatchTask {
+    fn drop(&mut self) {
+        if self.is_active() {
+            self.cancel();
+        }
+    }
+}
+
+/// Provider sending given results on watch, for tests and demos
+#[derive(Default, Clone)]
+pub struct MockGeolocation {
+    pub results: Vec<Result<Location, GeolocationError>>,
+    /// Set while the last watch is active, cleared when its task is cancelled
+    pub watching: Rc<Cell<bool>>,
+}
+
+impl GeolocationProvider for MockGeolocation {
+    fn watch(&self, callback: Callback<Result<Location, GeolocationError>>) -> Box<dyn Task> {
+        for result in self.results.iter() {
+            callback.emit(result.clone());
+        }
+        self.watching.set(true);
+        Box::new(MockTask(self.watching.clone()))
+    }
+}
+
+// mock watch, only tracks cancellation
+struct MockTask(Rc<Cell<bool>>);
+
+impl Task for MockTask {
+    fn is_active(&self) -> bool {
+        self.0.get()
+    }
+
+    fn cancel(&mut self) {
+        self.0.set(false);
+    }
+}
+
+impl Drop for MockTask {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// Locating state of map with its position watch
+pub struct Locator {
+    state: State,
+    provider: Rc<dyn GeolocationProvider>,
+    // position watch, cancelled when dropped
+    task: Option<Box<dyn Task>>,
+}
+
+impl Locator {
+    pub fn new(provider: Rc<dyn GeolocationProvider>) -> Self {
+        Locator {
+            state: State::default(),
+            provider,
+            task: None,
+        }
+    }
+    /// Replaces provider, used on next start
+    pub fn set_provider(&mut self, provider: Rc<dyn GeolocationProvider>) {
+        self.provider = provider;
+    }
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+    /// Starts watching position with results sent to callback, or stops if already locating
+    pub fn toggle(&mut self, follow: bool, callback: Callback<Result<Location, GeolocationError>>) {
+        if self.state.active() {
+            self.stop();
+        } else {
+            self.state.start(follow);
+            self.task = Some(self.provider.watch(callback));
+        }
+    }
+    /// Stops locating and cancels the watch
+    pub fn stop(&mut self) {
+        self.state.stop();
+        self.task = None;
+    }
+    /// Handles provider result, the watch is cancelled if locating stopped
+    pub fn receive(&mut self, result: Result<Location, GeolocationError>) -> Update {
+        let update = self.state.receive(result);
+        if !self.state.active() {
+            self.task = None;
+        }
+        update
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::LonLat;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_locator_1() {
+        let fix = |lon: f64| Location {
+            position: LonLat { lon, lat: 0.0 },
+            accuracy: 10.0,
+        };
+        let provider = MockGeolocation {
+            results: vec![
+                Ok(fix(10.0)),
+                Err(GeolocationError::Timeout),
+                Ok(fix(11.0)),
+                Err(GeolocationError::PermissionDenied),
+                Ok(fix(12.0)),
+            ],
+            ..MockGeolocation::default()
+        };
+        let watching = provider.watching.clone();
+        let mut locator = Locator::new(Rc::new(provider));
+        // results are queued like map messages
+        let queue = Rc::new(RefCell::new(vec![]));
+        let q = queue.clone();
+        let callback = Callback::from(move |result| q.borrow_mut().push(result));
+
+        locator.toggle(false, callback.clone());
+        assert!(watching.get());
+        let updates: Vec<Update> = queue
+            .borrow_mut()
+            .drain(..)
+            .map(|result| locator.receive(result))
+            .collect();
+        assert_eq!(
+            updates,
+            vec![
+                // first fix centers map
+                Update::Found(fix(10.0), Some(fix(10.0).position)),
+                // timeouts are reported without stopping
+                Update::Failed(GeolocationError::Timeout),
+                Update::Found(fix(11.0), None),
+                // denied permission stops locating and cancels the watch
+                Update::Failed(GeolocationError::PermissionDenied),
+                Update::Ignored,
+            ]
+        );
+        assert!(!locator.state().active());
+        assert!(!watching.get());
+
+        // toggling twice cancels the watch
+        locator.toggle(true, callback.clone());
+        assert!(locator.state().active());
+        assert!(watching.get());
+        locator.toggle(true, callback);
+        assert!(!locator.state().active());
+        assert!(!watching.get());
+        assert_eq!(locator.receive(Ok(fix(1.0))), Update::Ignored);
+    }
+}
@@ -12,22 +12,25 @@ pub struct Grid {
     vw_outer: Viewport,
     // layers to draw
     layers: Vec<TileLayer>,
+    // high-DPI screen
+    hidpi: bool,
 }
 
 impl Grid {
-    fn tile_rows(vw: &Viewport) -> Vec<Vec<TileModel>> {
+    // returns tiles on zoom level z, grouped by rows
+    fn tile_rows(vw: &Viewport, z: usize) -> Vec<Vec<TileModel>> {
         // group by rows
         let mut tile_rows = vec![];
-        for (_, group) in &vw.tiles().group_by(|t| t.y) {
+        for (_, group) in &vw.tiles_at(z).group_by(|t| t.y) {
             tile_rows.push(group.collect::<Vec<TileModel>>());
         }
         tile_rows
     }
 
     // returns pixel offset between viewports
-    fn tile_offset(vw: &Viewport, vw_outer: &Viewport) -> Px {
+    fn tile_offset(vw: &Viewport, vw_outer: &Viewport, z: usize) -> Px {
         // take nw tile of outer viewport
-        let tile = TileModel::from_lonlat(vw_outer.lon_min, vw_outer.lat_max, vw.z);
+        let tile = TileModel::from_lonlat(vw_outer.lon_min, vw_outer.lat_max, z);
         // return pixel offset from inner viewport
         vw.pixel_offset(&tile)
     }
@@ -40,6 +43,7 @@ pub struct Prop {
     pub vw: Viewport,
     pub vw_outer: Viewport,
     pub layers: Vec<TileLayer>,
+    pub hidpi: bool,
 }
 
 impl Component for Grid {
@@ -51,6 +55,7 @@ impl Component for Grid {
             vw: prop.vw,
             vw_outer: prop.vw_outer,
             layers: prop.layers,
+            hidpi: prop.hidpi,
         }
     }
 
@@ -69,42 +74,48 @@ impl Component for Grid {
             self.layers = prop.layers;
             changed = true;
         }
+        if self.hidpi != prop.hidpi {
+            self.hidpi = prop.hidpi;
+            changed = true;
+        }
         changed
     }
 
     fn view(&self) -> Html<Self> {
-        let tile_rows = Grid::tile_rows(&self.vw_outer);
-        let tile_offset = Grid::tile_offset(&self.vw, &self.vw_outer);
         html! {
-            <div class="remap-tile-grid remap-noselect", draggable="false",
-                style={format!("transform: translate({}px, {}px)", &tile_offset.x, &tile_offset.y)},>
-                    { for self.layers.iter().map(|l| tile_layer(l, &tile_rows)) }
+            <div class="remap-tile-grid remap-noselect", draggable="false",>
+                { for self.layers.iter().map(|l| tile_layer(l, &self.vw, &self.vw_outer, self.hidpi)) }
             </div>
         }
     }
 }
 
 // draws tile layer
-fn tile_layer(layer: &TileLayer, tile_rows: &[Vec<TileModel>]) -> Html<Grid> {
+fn tile_layer(layer: &TileLayer, vw: &Viewport, vw_outer: &Viewport, hidpi: bool) -> Html<Grid> {
+    // tiles are fetched from source zoom level and scaled to fit current zoom
+    let z = layer.source_zoom(vw.z, hidpi);
+    let tile_rows = Grid::tile_rows(vw_outer, z);
+    let tile_offset = Grid::tile_offset(vw, vw_outer, z);
     html! {
-        <div class="remap-tile-layer",>
-            { for tile_rows.into_iter().map(|tr| tile_row(tr, layer)) }
+        <div class="remap-tile-layer",
+            style={format!("transform: translate({}px, {}px)", &tile_offset.x, &tile_offset.y)},>
+            { for tile_rows.iter().map(|tr| tile_row(tr, layer, vw.z, hidpi)) }
         </div>
     }
 }
 
 // draws tile row
-fn tile_row(tiles: &[TileModel], layer: &TileLayer) -> Html<Grid> {
+fn tile_row(tiles: &[TileModel], layer: &TileLayer, z: usize, hidpi: bool) -> Html<Grid> {
     html! {
         <div class="remap-tile-row",>
-            { for tiles.iter().map(|t| tile(t.clone(), layer)) }
+            { for tiles.iter().map(|t| tile(t.clone(), layer, t.size_at(z), hidpi)) }
         </div>
     }
 }
 
 // draws tile
-fn tile(tile: TileModel, layer: &TileLayer) -> Html<Grid> {
+fn tile(tile: TileModel, layer: &TileLayer, size: i64, hidpi: bool) -> Html<Grid> {
     html! {
-        <Tile: tile=tile, layer=layer.clone(),/>
+        <Tile: tile=tile, layer=layer.clone(), size=size, hidpi=hidpi,/>
     }
 }
@@ -0,0 +1,137 @@
+use super::canvas::device_ratio;
+use crate::model::{HeatmapLayer, Viewport};
+use std::rc::Rc;
+use stdweb::js;
+use stdweb::web::html_element::CanvasElement;
+use stdweb::web::TypedArray;
+use yew::services::render::{RenderService, RenderTask};
+use yew::{html, Component, ComponentLink, Html, NodeRef, Properties, ShouldRender};
+
+/// Heatmap layer drawn on canvas on top of tile grid
+pub struct Heatmap {
+    link: ComponentLink<Self>,
+    // visible viewport
+    vw: Viewport,
+    layer: HeatmapLayer,
+    // region covered by drawn image, none until first draw
+    region: Option<Viewport>,
+    canvas: NodeRef,
+    // render service vars
+    render: RenderService,
+    render_task: Option<RenderTask>,
+}
+
+pub enum Msg {
+    Draw,
+}
+
+#[derive(Properties, PartialEq, Clone, Default)]
+pub struct Prop {
+    pub vw: Viewport,
+    pub layer: HeatmapLayer,
+}
+
+impl Heatmap {
+    // draws on next animation frame, pending draw is reused
+    fn schedule(&mut self) {
+        if self.render_task.is_none() {
+            let cb = self.link.send_back(|_| Msg::Draw);
+            self.render_task = Some(self.render.request_animation_frame(cb));
+        }
+    }
+
+    fn draw(&mut self) {
+        let canvas = match NodeRef::try_into::<CanvasElement>(&self.canvas) {
+            Some(canvas) => canvas,
+            None => return,
+        };
+        // image is computed in device pixels, css size of canvas stays the region size
+        let ratio = device_ratio();
+        let region = HeatmapLayer::region(&self.vw);
+        let (w, h) = HeatmapLayer::image_size(&region, ratio);
+        if w == 0 || h == 0 {
+            return;
+        }
+        let image: TypedArray<u8> = self.layer.image(&region, ratio).as_slice().into();
+        self.region = Some(region);
+        js! { @(no_return)
+            var canvas = @{canvas};
+            var w = @{w as u32};
+            var h = @{h as u32};
+            var data = new Uint8ClampedArray(@{image}.buffer);
+            canvas.width = w;
+            canvas.height = h;
+            canvas.getContext("2d").putImageData(new ImageData(data, w, h), 0, 0);
+        }
+    }
+}
+
+impl Component for Heatmap {
+    type Message = Msg;
+    type Properties = Prop;
+
+    fn create(prop: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Heatmap {
+            link,
+            vw: prop.vw,
+            layer: prop.layer,
+            region: None,
+            canvas: NodeRef::default(),
+            render: RenderService::new(),
+            render_task: None,
+        }
+    }
+
+    fn mounted(&mut self) -> ShouldRender {
+        self.schedule();
+        false
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Draw => {
+                self.render_task = None;
+                self.draw();
+            }
+        }
+        // canvas is moved and sized to drawn region
+        true
+    }
+
+    fn change(&mut self, prop: Self::Properties) -> ShouldRender {
+        // points are compared by reference, data is not expected to change in place
+        let (a, b) = (&self.layer, &prop.layer);
+        let changed = !Rc::ptr_eq(&a.points, &b.points)
+            || a.radius != b.radius
+            || a.blur != b.blur
+            || a.max != b.max
+            || a.gradient != b.gradient
+            || a.opacity != b.opacity;
+        // panning inside drawn region only moves the canvas
+        let uncovered = match self.region {
+            Some(ref region) => !HeatmapLayer::covers(region, &prop.vw),
+            None => true,
+        };
+        if changed || uncovered {
+            self.schedule();
+        }
+        let moved = self.vw != prop.vw;
+        self.vw = prop.vw;
+        self.layer = prop.layer;
+        // canvas is redrawn directly, element changes only with position
+        moved
+    }
+
+    fn view(&self) -> Html<Self> {
+        let region = self.region.unwrap_or(self.vw);
+        let size = region.size();
+        let offset = self
+            .vw
+            .point_offset(&(region.lon_min, region.lat_max).into());
+        html! {
+            <canvas class="remap-heatmap-layer", ref=self.canvas.clone(),
+                style=format!("width: {}px; height: {}px; transform: translate({}px, {}px)",
+                    size.x, size.y, offset.x, offset.y),></canvas>
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::model::Px;
-use crate::state::{inertia, panning};
+use crate::state::{inertia, panning, press};
 use stdweb::js;
 use stdweb::unstable::TryInto;
 use stdweb::web::event::{ITouchEvent, TouchEnd, TouchMove, TouchStart};
@@ -9,11 +9,16 @@ use yew::services::render::{RenderService, RenderTask};
 use yew::{html, Callback, Component, ComponentLink, Html, Properties, ShouldRender};
 
 pub enum InputEvent {
+    // press released without panning
     Click,
     DoubleClick,
     MoveBegin,
     Move,
     MoveEnd,
+    // mouse moved without panning
+    Hover,
+    // mouse left map
+    Leave,
 }
 
 pub struct Input {
@@ -24,6 +29,7 @@ pub struct Input {
     // state handlers
     inertia: inertia::State,
     panning: panning::State,
+    press: press::State,
     // render service vars
     render: RenderService,
     render_task: Option<RenderTask>,
@@ -36,14 +42,19 @@ pub enum Msg {
     Click(f64, f64),
     DoubleClick(f64, f64),
     Move(f64, f64),
+    // screen position followed by position relative to element
+    MouseMove(f64, f64, f64, f64),
     MoveBegin(f64, f64),
     MoveRelease,
+    MouseLeave,
     Decelerate(f64, f64),
     Stop,
 }
 
 #[derive(Properties, Default, PartialEq, Clone)]
 pub struct Prop {
+    /// Element id, unique per map, touch listeners are attached to the element
+    pub id: String,
     pub oninput: Option<Callback<(Px, InputEvent)>>,
 }
 
@@ -63,11 +74,12 @@ impl Component for Input {
     fn create(prop: Self::Properties, mut link: ComponentLink<Self>) -> Self {
         link.send_self(Msg::Init);
         Self {
-            id: String::from("remap_input"),
+            id: prop.id,
             link: link,
             oninput: prop.oninput,
             panning: Default::default(),
             inertia: Default::default(),
+            press: Default::default(),
             render: RenderService::new(),
             render_task: None,
             handles: vec![],
@@ -105,7 +117,10 @@ impl Component for Input {
                 }
             }
             Msg::Click(x, y) => {
-                self.notify((x, y).into(), InputEvent::Click);
+                // browser clicks also after panning, those are not map clicks
+                if self.press.is_click() {
+                    self.notify((x, y).into(), InputEvent::Click);
+                }
             }
             Msg::DoubleClick(x, y) => {
                 self.notify((x, y).into(), InputEvent::DoubleClick);
@@ -113,14 +128,23 @@ impl Component for Input {
             Msg::Move(x, y) => {
                 if self.panning.status() == panning::Status::Panning {
                     self.panning.set_position((x, y));
+                    self.press.pan(self.panning.offset());
                     self.notify(self.panning.offset().into(), InputEvent::Move);
                 }
             }
+            Msg::MouseMove(x, y, offset_x, offset_y) => {
+                if self.panning.status() == panning::Status::Idle {
+                    self.notify((offset_x, offset_y).into(), InputEvent::Hover);
+                } else {
+                    return self.update(Msg::Move(x, y));
+                }
+            }
             Msg::MoveBegin(x, y) => {
                 if self.panning.status() != panning::Status::Idle {
                     self.notify((0, 0).into(), InputEvent::MoveEnd);
                 }
                 self.panning.begin((x, y));
+                self.press.begin();
                 self.notify((0, 0).into(), InputEvent::MoveBegin);
             }
             Msg::MoveRelease => {
@@ -130,6 +154,10 @@ impl Component for Input {
                     self.link.send_self(Msg::Decelerate(perfnow, perfnow));
                 }
             }
+            Msg::MouseLeave => {
+                self.notify((0, 0).into(), InputEvent::Leave);
+                return self.update(Msg::MoveRelease);
+            }
             Msg::Decelerate(t1, t0) => {
                 if self.panning.status() == panning::Status::Free {
                     let dt = t1 - t0;
@@ -169,8 +197,9 @@ impl Component for Input {
                 ondoubleclick=|e| Msg::DoubleClick(e.offset_x(), e.offset_y()),
                 onmousedown=|e| Msg::MoveBegin(e.screen_x() as f64, e.screen_y() as f64),
                 onmouseup=|_| Msg::MoveRelease,
-                onmouseleave=|_| Msg::MoveRelease,
-                onmousemove=|e| Msg::Move(e.screen_x() as f64, e.screen_y() as f64),>
+                onmouseleave=|_| Msg::MouseLeave,
+                onmousemove=|e| Msg::MouseMove(
+                    e.screen_x() as f64, e.screen_y() as f64, e.offset_x(), e.offset_y()),>
             </div>
         }
     }
@@ -1,13 +1,44 @@
-use super::{Grid, Input, InputEvent};
-use crate::model::{LonLat, Px, TileLayer, Viewport};
+use super::control::{self, Action, MapControl};
+use super::raw::RawHtml;
+use super::{
+    BrowserGeolocation, Canvas, GeoJson, GeolocationProvider, Grid, Heatmap, Input, InputEvent,
+    Locator, MarkerCanvas, Markers, Measure, VectorTiles,
+};
+use crate::model::{
+    attributions, hit_markers, Cluster, ClusterIndex, Clustering, GeoJsonLayer, GeolocationError,
+    HeatmapLayer, Location, LonLat, Marker, MarkerHit, Measurement, Popup, Px, Renderer, Retina,
+    ShapeCache, TileLayer, VectorTileLayer, Viewport,
+};
 use crate::state::layer;
+use crate::state::locate::Update;
+use serde_json::{Map as JsonMap, Value};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use stdweb::js;
 use stdweb::unstable::TryInto;
-use stdweb::web::event::ResizeEvent;
+use stdweb::web::event::{IKeyboardEvent, KeyDownEvent, ResizeEvent};
 use stdweb::web::{
     document, window, Element, EventListenerHandle, HtmlElement, IEventTarget, IHtmlElement,
-    INonElementParentNode,
+    INonElementParentNode, Rect,
 };
-use yew::{html, Component, ComponentLink, Html, ShouldRender};
+use stdweb::Value as JsValue;
+use yew::services::interval::{IntervalService, IntervalTask};
+use yew::{
+    html, Callback, Children, Component, ComponentLink, Html, Properties, Renderable, ShouldRender,
+};
+
+// zoom level bounds
+const MIN_ZOOM: i8 = 1;
+const MAX_ZOOM: i8 = 22;
+// space left between auto-panned popup and map edges
+const POPUP_PADDING: i64 = 10;
+// extra distance in pixels for hitting vector features
+const HIT_TOLERANCE: f64 = 3.0;
+// container size polling interval without ResizeObserver
+const RESIZE_POLL_MS: u64 = 500;
+// number of created maps, numbers element ids of further maps
+static MAP_COUNT: AtomicUsize = AtomicUsize::new(0);
 
 pub struct Map {
     link: ComponentLink<Self>,
@@ -18,12 +49,132 @@ pub struct Map {
     // element width, height in pixels
     width: i32,
     height: i32,
+    // device pixel ratio above 1
+    hidpi: bool,
+    // map element is shown fullscreen
+    fullscreen: bool,
     // if set to some, indicates a map move in progress
     movement: Option<Px>,
     // state handlers
     layers: layer::State,
+    // last tile layers given in properties
+    layers_prop: Option<Vec<TileLayer>>,
+    // vector tile layers on top of raster tiles
+    vector_tiles: Vec<VectorTileLayer>,
+    // overlays
+    geojson: Vec<GeoJsonLayer>,
+    // projected vector layer shapes for hit testing
+    hit_caches: Vec<ShapeCache>,
+    // attributions of visible layers, refreshed when layers change
+    attributions: Vec<String>,
+    // layer and feature index under mouse
+    hovered: Option<(usize, usize)>,
+    // last mouse position on map
+    cursor: Option<LonLat>,
+    heatmaps: Vec<HeatmapLayer>,
+    markers: Vec<Marker>,
+    // marker clustering options
+    clustering: Option<Clustering>,
+    // marker drawing backend
+    marker_renderer: Renderer,
+    // marker clusters on all zoom levels
+    cluster_index: Option<ClusterIndex>,
+    // marker clusters on current zoom level
+    clusters: Option<Rc<Vec<Cluster>>>,
+    // user location and its position watch
+    locator: Locator,
+    // measurement in progress or last finished one, clicks add vertices while set
+    measure: Option<Measurement>,
+    // currently open popup
+    popup: Option<Popup>,
+    // last popup given in properties
+    popup_prop: Option<Popup>,
+    // popup html node, kept while html is unchanged
+    popup_html: RawHtml,
+    // popup content if popup has no html
+    children: Children<Map>,
+    // controls on top of map
+    controls: Vec<MapControl>,
+    // last controls given in properties
+    controls_prop: Option<Vec<MapControl>>,
+    // map event handler
+    onevent: Option<Callback<Event>>,
     // dom callback handles
     handles: Vec<EventListenerHandle>,
+    // container size observer and its callback, disconnected on destroy
+    resize_observer: Option<JsValue>,
+    // container size polling if observer is not supported
+    interval: IntervalService,
+    resize_poll: Option<IntervalTask>,
+}
+
+#[derive(Properties, Default)]
+pub struct Prop {
+    /// Raster tile layers, default layer if not set
+    pub layers: Option<Vec<TileLayer>>,
+    /// Vector tile layers drawn on top of raster tiles
+    pub vector_tiles: Vec<VectorTileLayer>,
+    /// Vector layers drawn below markers
+    pub geojson: Vec<GeoJsonLayer>,
+    /// Heatmap layers drawn below markers
+    pub heatmaps: Vec<HeatmapLayer>,
+    pub markers: Vec<Marker>,
+    /// Groups nearby markers to clusters if set
+    pub clustering: Option<Clustering>,
+    /// Marker drawing backend, canvas suits many markers.
+    /// Markers with html content are always drawn as elements.
+    pub marker_renderer: Renderer,
+    /// Popup to open, opened again whenever it changes
+    pub popup: Option<Popup>,
+    /// User location source of locate control, browser geolocation if not set
+    pub geolocation: Option<Rc<dyn GeolocationProvider>>,
+    /// Content of popups without html
+    pub children: Children<Map>,
+    /// Map controls, zoom buttons if not set
+    pub controls: Option<Vec<MapControl>>,
+    pub onevent: Option<Callback<Event>>,
+}
+
+/// Events sent from map
+pub enum Event {
+    MarkerClick(usize),
+    PopupOpen(Popup),
+    PopupClose,
+    /// GeoJSON feature clicked
+    FeatureClick(FeatureEvent),
+    /// GeoJSON feature under mouse changed, none if mouse left features
+    FeatureHover(Option<FeatureEvent>),
+    /// Tile layer visibility changed from layer switcher
+    LayerChange(LayerEvent),
+    /// Map entered or left fullscreen
+    Fullscreen(bool),
+    /// User position found or updated
+    LocationFound(Location),
+    /// Locating failed, it is stopped if permission is denied or geolocation unsupported
+    LocationError(GeolocationError),
+    /// Measurement finished with double click
+    MeasureEnd(Measurement),
+}
+
+/// Tile layer shown or hidden
+pub struct LayerEvent {
+    /// Index of tile layer
+    pub layer: usize,
+    pub name: String,
+    pub visible: bool,
+}
+
+/// Vector feature hit by mouse
+///
+/// Only GeoJSON layers are hit-tested, vector tile features are drawn
+/// but never reported, as their decoded tiles stay inside the layer component.
+pub struct FeatureEvent {
+    /// Index of GeoJSON layer
+    pub layer: usize,
+    /// Index of feature in layer
+    pub feature: usize,
+    pub id: Option<Value>,
+    pub properties: Option<JsonMap<String, Value>>,
 }
 
 pub enum Msg {
@@ -32,6 +183,13 @@ pub enum Msg {
     Goto(Px, i8), // centers immediately to point with given zoom
     Zoom(i8),
     Input(Px, InputEvent),
+    MarkerClick(usize),
+    ClusterClick(usize),
+    OpenPopup(Popup),
+    ClosePopup,
+    AutoPan,
+    Action(Action), // sent from controls
+    Location(Result<Location, GeolocationError>),
 }
 
 impl Map {
@@ -72,63 +230,394 @@ impl Map {
             (vw, vw.clone())
         }
     }
+    // rebuilds cluster index from markers
+    fn build_clusters(&mut self) {
+        self.cluster_index = self.clustering.as_ref().map(|options| {
+            let points: Vec<LonLat> = self.markers.iter().map(|m| m.position).collect();
+            ClusterIndex::new(&points, options)
+        });
+        self.refresh_clusters();
+    }
+    // picks clusters of current zoom level
+    fn refresh_clusters(&mut self) {
+        self.clusters = self.cluster_index.as_ref().map(|i| i.clusters(self.zoom));
+    }
+    // finds topmost canvas drawn marker or cluster at viewport pixel position
+    fn hit_marker(&self, pos: &Px) -> Option<MarkerHit> {
+        if self.marker_renderer != Renderer::Canvas {
+            return None;
+        }
+        let (vw, _) = self.calc_viewports();
+        hit_markers(
+            &self.markers,
+            self.clusters.as_ref().map(|c| c.as_slice()),
+            &vw,
+            pos,
+        )
+    }
+    // finds topmost visible GeoJSON feature at viewport pixel position,
+    // vector tile layers are not hit-tested
+    fn hit_feature(&mut self, pos: &Px) -> Option<(usize, usize)> {
+        let (vw, _) = self.calc_viewports();
+        let p = vw.pixels().translate(pos);
+        self.hit_caches
+            .resize_with(self.geojson.len(), Default::default);
+        for (i, layer) in self.geojson.iter().enumerate().rev() {
+            if layer.visible {
+                let shapes = self.hit_caches[i].shapes(layer, vw.z);
+                if let Some(f) = layer.hit(&shapes, vw.z, &p, HIT_TOLERANCE) {
+                    return Some((i, f));
+                }
+            }
+        }
+        None
+    }
+    // creates event of hit feature
+    fn feature_event(&self, (layer, feature): (usize, usize)) -> Option<FeatureEvent> {
+        let f = self.geojson.get(layer)?.features.get(feature)?;
+        Some(FeatureEvent {
+            layer,
+            feature,
+            id: f.id.clone(),
+            properties: f.properties.clone(),
+        })
+    }
+    // function to send back events
+    fn notify(&self, e: Event) {
+        if let Some(ref cb) = self.onevent {
+            cb.emit(e);
+        }
+    }
+    // draws popup if open
+    fn view_popup(&self, vw: &Viewport) -> Html<Self> {
+        match self.popup {
+            Some(ref popup) => {
+                let offset = vw.point_offset(&popup.position).translate(&popup.offset);
+                let content = match popup.html {
+                    Some(ref html) => self.popup_html.view(0, "remap-popup-content", html),
+                    None => html! {
+                        <div class="remap-popup-content",>{ self.children.render() }</div>
+                    },
+                };
+                html! {
+                    <div class="remap-popup",
+                        style={format!("transform: translate({}px, {}px)", &offset.x, &offset.y)},>
+                        <div id=self.popup_id(), class=format!("remap-popup-box {}", &popup.class),>
+                            <a class="remap-popup-close", onclick=|_| Msg::ClosePopup,>{ "×" }</a>
+                            { content }
+                            <div class="remap-popup-tip",></div>
+                        </div>
+                    </div>
+                }
+            }
+            None => html! {},
+        }
+    }
+    // handles input while measuring, panning works as usual
+    fn measure_input(&mut self, measure: &mut Measurement, pos: Px, e: InputEvent) -> ShouldRender {
+        let (vw, _) = self.calc_viewports();
+        let ll = vw.pixels().translate(&pos).lonlat(vw.z);
+        match e {
+            InputEvent::Click => {
+                measure.click(ll);
+                true
+            }
+            InputEvent::DoubleClick => {
+                if measure.finished {
+                    return false;
+                }
+                measure.finish();
+                self.notify(Event::MeasureEnd(measure.clone()));
+                true
+            }
+            InputEvent::Hover => {
+                self.cursor = Some(ll);
+                // rubber band follows cursor
+                !measure.finished
+            }
+            InputEvent::Leave => {
+                self.cursor = None;
+                true
+            }
+            InputEvent::MoveBegin | InputEvent::MoveEnd => {
+                self.finish_panning();
+                true
+            }
+            InputEvent::Move => {
+                self.movement = Some(pos);
+                true
+            }
+        }
+    }
+    // draws user location with accuracy circle
+    fn view_location(&self, vw: &Viewport) -> Html<Self> {
+        match self.locator.state().location() {
+            Some(location) => {
+                let offset = vw.point_offset(&location.position);
+                let r = location.accuracy_px(vw.z).round();
+                html! {
+                    <div class="remap-location",
+                        style=format!("transform: translate({}px, {}px)", offset.x, offset.y),>
+                        <div class="remap-location-accuracy",
+                            style=format!("width: {0}px; height: {0}px; margin: -{1}px", 2.0 * r, r),></div>
+                        <div class="remap-location-dot",></div>
+                    </div>
+                }
+            }
+            None => html! {},
+        }
+    }
+    // draws vector layer with its renderer
+    fn view_vector(&self, layer: &GeoJsonLayer, vw: &Viewport, vw_outer: &Viewport) -> Html<Self> {
+        match layer.renderer {
+            Renderer::Svg => html! {
+                <GeoJson: vw=vw, vw_outer=vw_outer, layer=layer.clone(), />
+            },
+            Renderer::Canvas => html! {
+                <Canvas: vw=vw, vw_outer=vw_outer, layer=layer.clone(), />
+            },
+        }
+    }
+    // single raster layer used when layers are not given
+    fn default_layers() -> Vec<TileLayer> {
+        vec![TileLayer {
+            name: "Neighbourhood".into(),
+            retina: Retina::Suffix,
+            attribution: Some(
+                "Maps © <a href=\"https://www.thunderforest.com\">Thunderforest</a>, \
+                 Data © <a href=\"https://www.openstreetmap.org/copyright\">OpenStreetMap contributors</a>"
+                    .into(),
+            ),
+            ..TileLayer::with_values(
+                "https://tile.thunderforest.com/neighbourhood/{z}/{x}/{y}{r}.png?apikey={apikey}",
+                &[("apikey", "9d61ff3f272b4bbaa7d9c0f63ad34177")],
+            )
+            .expect("valid tile url template")
+        }]
+    }
+    // collects attributions of visible layers in drawing order
+    fn refresh_attributions(&mut self) {
+        self.attributions = attributions(
+            self.layers.layers(),
+            &self.vector_tiles,
+            &self.geojson,
+            &self.heatmaps,
+        );
+    }
+    // sends layer change events of changed layers
+    fn notify_layers(&self, changed: &[usize]) {
+        for &idx in changed {
+            if let Some(layer) = self.layers.get(idx) {
+                self.notify(Event::LayerChange(LayerEvent {
+                    layer: idx,
+                    name: layer.name.clone(),
+                    visible: layer.visible,
+                }));
+            }
+        }
+    }
+    // observes container size, polling if ResizeObserver is not supported
+    fn observe_resize(&mut self) {
+        let cb = self.link.send_back(|_| Msg::Resize);
+        let notify = move || cb.emit(());
+        let observer = js! {
+            var notify = @{notify};
+            var el = document.getElementById(@{&self.id});
+            if (!el || typeof ResizeObserver === "undefined") {
+                notify.drop();
+                return null;
+            }
+            var observer = new ResizeObserver(function() { notify(); });
+            observer.observe(el);
+            return { observer: observer, notify: notify };
+        };
+        match observer {
+            JsValue::Null => {
+                let cb = self.link.send_back(|_| Msg::Resize);
+                let task = self
+                    .interval
+                    .spawn(Duration::from_millis(RESIZE_POLL_MS), cb);
+                self.resize_poll = Some(task);
+            }
+            observer => self.resize_observer = Some(observer),
+        }
+    }
+    // popup element id, unique per map
+    fn popup_id(&self) -> String {
+        format!("{}_popup", self.id)
+    }
+    // returns bounding rect of element by id
+    fn element_rect(id: &str) -> Option<Rect> {
+        document()
+            .get_element_by_id(id)
+            .and_then(|el: Element| el.try_into().ok())
+            .map(|html_el: HtmlElement| html_el.get_bounding_client_rect())
+    }
 }
 
 impl Component for Map {
     type Message = Msg;
-    type Properties = ();
+    type Properties = Prop;
 
-    fn create(_: Self::Properties, mut link: ComponentLink<Self>) -> Self {
+    fn create(prop: Self::Properties, mut link: ComponentLink<Self>) -> Self {
         link.send_self(Msg::Init);
-        Map {
+        if let Some(ref popup) = prop.popup {
+            link.send_self(Msg::OpenPopup(popup.clone()));
+        }
+        let mut map = Map {
             link: link,
-            id: String::from("remap_root"),
+            id: match MAP_COUNT.fetch_add(1, Ordering::Relaxed) {
+                0 => String::from("remap_root"),
+                n => format!("remap_root_{}", n),
+            },
             center: LonLat {
                 lon: 29.8,
                 lat: 62.6,
             },
             height: 256,
             width: 256,
+            hidpi: false,
+            fullscreen: false,
             movement: None,
             zoom: 4,
-            // add single raster layer as default
-            // TODO: parametrize
-            layers: layer::State::new(vec![TileLayer::new(
-                "https://tile.thunderforest.com/neighbourhood",
-                ".png?apikey=9d61ff3f272b4bbaa7d9c0f63ad34177",
-            )]),
+            layers: layer::State::new(prop.layers.clone().unwrap_or_else(Map::default_layers)),
+            layers_prop: prop.layers,
+            vector_tiles: prop.vector_tiles,
+            geojson: prop.geojson,
+            hit_caches: vec![],
+            attributions: vec![],
+            hovered: None,
+            cursor: None,
+            heatmaps: prop.heatmaps,
+            markers: prop.markers,
+            clustering: prop.clustering,
+            marker_renderer: prop.marker_renderer,
+            cluster_index: None,
+            clusters: None,
+            locator: Locator::new(
+                prop.geolocation
+                    .unwrap_or_else(|| Rc::new(BrowserGeolocation::default())),
+            ),
+            measure: None,
+            popup: None,
+            popup_prop: prop.popup,
+            popup_html: RawHtml::default(),
+            children: prop.children,
+            controls: prop.controls.clone().unwrap_or_else(MapControl::defaults),
+            controls_prop: prop.controls,
+            onevent: prop.onevent,
             handles: vec![],
+            resize_observer: None,
+            interval: IntervalService::new(),
+            resize_poll: None,
+        };
+        map.build_clusters();
+        map.refresh_attributions();
+        map
+    }
+
+    fn change(&mut self, prop: Self::Properties) -> ShouldRender {
+        // opened popup is drawn by its own message
+        if self.popup_prop != prop.popup {
+            self.popup_prop = prop.popup.clone();
+            if let Some(popup) = prop.popup {
+                self.link.send_self(Msg::OpenPopup(popup));
+            }
+        }
+        // layer choices are kept until layers change
+        let layers_changed = self.layers_prop != prop.layers
+            || self.vector_tiles != prop.vector_tiles
+            || self.geojson != prop.geojson
+            || self.heatmaps != prop.heatmaps;
+        if self.layers_prop != prop.layers {
+            self.layers =
+                layer::State::new(prop.layers.clone().unwrap_or_else(Map::default_layers));
+            self.layers_prop = prop.layers;
+        }
+        if layers_changed {
+            self.vector_tiles = prop.vector_tiles;
+            self.geojson = prop.geojson;
+            self.heatmaps = prop.heatmaps;
+            self.refresh_attributions();
+        }
+        let markers_changed = self.markers != prop.markers
+            || self.clustering != prop.clustering
+            || self.marker_renderer != prop.marker_renderer;
+        if self.markers != prop.markers || self.clustering != prop.clustering {
+            self.markers = prop.markers;
+            self.clustering = prop.clustering;
+            self.build_clusters();
+        }
+        self.marker_renderer = prop.marker_renderer;
+        // controls are kept with their caches until new control instances are given
+        let controls_changed = self.controls_prop != prop.controls;
+        if controls_changed {
+            self.controls = prop.controls.clone().unwrap_or_else(MapControl::defaults);
+            self.controls_prop = prop.controls;
         }
+        // children can not be compared, they are drawn only in popups without html
+        let children_shown = match self.popup {
+            Some(ref popup) => popup.html.is_none(),
+            None => false,
+        };
+        if let Some(geolocation) = prop.geolocation {
+            self.locator.set_provider(geolocation);
+        }
+        self.children = prop.children;
+        self.onevent = prop.onevent;
+        layers_changed || markers_changed || controls_changed || children_shown
     }
 
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
             Msg::Init => {
-                // make resize event handler
+                // kept next to the container observer: pixel ratio changes resize only the window
                 let cb = self.link.send_back(|_| Msg::Resize);
                 self.handles
                     .push(window().add_event_listener(move |_: ResizeEvent| cb.emit(())));
+                // container may resize without window, eg. in flex layouts
+                self.observe_resize();
+                // close popup on escape
+                let cb = self.link.send_back(|_| Msg::ClosePopup);
+                self.handles
+                    .push(document().add_event_listener(move |e: KeyDownEvent| {
+                        if e.key() == "Escape" {
+                            cb.emit(());
+                        }
+                    }));
                 // send initial resize event
                 self.link.send_self(Msg::Resize);
                 // no need for immediate redraw
                 false
             }
             Msg::Resize => {
-                // get element
-                document()
-                    .get_element_by_id(&self.id)
-                    .map(|el: Element| {
-                        // try into html element, which has the rect methods
-                        el.try_into()
-                            .map(|html_el: HtmlElement| {
-                                // set width, height from rect object
-                                let r = html_el.get_bounding_client_rect();
-                                self.width = r.get_width() as i32;
-                                self.height = r.get_height() as i32;
-                            })
-                            .ok()
-                    })
-                    .is_some()
+                // pixel ratio may change when window is moved between screens
+                let ratio: f64 = js! { return window.devicePixelRatio || 1; }
+                    .try_into()
+                    .unwrap_or(1.0);
+                let hidpi = ratio > 1.0;
+                let fullscreen: bool = js! {
+                    var el = document.getElementById(@{&self.id});
+                    return !!el && (document.fullscreenElement || document.webkitFullscreenElement) === el;
+                }
+                .try_into()
+                .unwrap_or(false);
+                let toggled = fullscreen != self.fullscreen;
+                if toggled {
+                    self.fullscreen = fullscreen;
+                    self.notify(Event::Fullscreen(fullscreen));
+                }
+                // set width, height from element rect
+                let (width, height) = match Map::element_rect(&self.id) {
+                    Some(r) => (r.get_width() as i32, r.get_height() as i32),
+                    None => (self.width, self.height),
+                };
+                // polling resizes often without changes
+                let changed = (width, height, hidpi) != (self.width, self.height, self.hidpi);
+                self.width = width;
+                self.height = height;
+                self.hidpi = hidpi;
+                changed || toggled
             }
             Msg::Goto(px, z) => {
                 // console!(log, &(px.x as i32), &(px.y as i32));
@@ -138,9 +627,52 @@ impl Component for Map {
                 true
             }
             Msg::Input(pos, e) => {
+                // measurement is taken out while handling input and put back after
+                if let Some(mut measure) = self.measure.take() {
+                    let render = self.measure_input(&mut measure, pos, e);
+                    self.measure = Some(measure);
+                    return render;
+                }
                 match e {
                     InputEvent::Click => {
-                        // TODO
+                        // close popup on map click
+                        self.link.send_self(Msg::ClosePopup);
+                        // canvas drawn markers are on top of vector layers
+                        match self.hit_marker(&pos) {
+                            Some(MarkerHit::Marker(idx)) => {
+                                self.link.send_self(Msg::MarkerClick(idx));
+                                return true;
+                            }
+                            Some(MarkerHit::Cluster(idx)) => {
+                                self.link.send_self(Msg::ClusterClick(idx));
+                                return true;
+                            }
+                            None => (),
+                        }
+                        if let Some(e) = self.hit_feature(&pos).and_then(|h| self.feature_event(h))
+                        {
+                            self.notify(Event::FeatureClick(e));
+                        }
+                    }
+                    InputEvent::Hover => {
+                        let (vw, _) = self.calc_viewports();
+                        self.cursor = Some(vw.pixels().translate(&pos).lonlat(vw.z));
+                        let tracking = self
+                            .controls
+                            .iter()
+                            .any(|c| c.enabled && c.control.tracks_cursor());
+                        let hit = self.hit_feature(&pos);
+                        if hit == self.hovered {
+                            return tracking;
+                        }
+                        self.hovered = hit;
+                        self.notify(Event::FeatureHover(hit.and_then(|h| self.feature_event(h))));
+                    }
+                    InputEvent::Leave => {
+                        self.cursor = None;
+                        if self.hovered.take().is_some() {
+                            self.notify(Event::FeatureHover(None));
+                        }
                     }
                     InputEvent::DoubleClick => {
                         self.link.send_self(Msg::Goto(pos, self.zoom as i8 + 1));
@@ -159,35 +691,221 @@ impl Component for Map {
             }
             Msg::Zoom(z) => {
                 //console!(log, "zoom");
-                if z >= 1 && z <= 18 {
+                if z >= MIN_ZOOM && z <= MAX_ZOOM && z as usize != self.zoom {
                     self.zoom = z as usize;
+                    self.refresh_clusters();
                 }
                 true
             }
+            Msg::MarkerClick(idx) => {
+                if let Some(marker) = self.markers.get(idx) {
+                    if let Some(ref html) = marker.popup {
+                        // open popup above the marker anchor
+                        self.link.send_self(Msg::OpenPopup(Popup {
+                            position: marker.position,
+                            html: Some(html.clone()),
+                            offset: (0, -marker.anchor.y).into(),
+                            class: String::new(),
+                        }));
+                    }
+                }
+                self.notify(Event::MarkerClick(idx));
+                false
+            }
+            Msg::ClusterClick(idx) => {
+                let cluster = self.clusters.as_ref().and_then(|c| c.get(idx).cloned());
+                if let Some(cluster) = cluster {
+                    // zoom in until cluster bounds fill the map
+                    let z = Viewport::fit_zoom(
+                        &cluster.nw,
+                        &cluster.se,
+                        (self.width, self.height),
+                        MAX_ZOOM as usize,
+                    )
+                    .max(self.zoom + 1)
+                    .min(MAX_ZOOM as usize);
+                    self.center = cluster.nw.px(z).avg(&cluster.se.px(z)).lonlat(z);
+                    self.link.send_self(Msg::Zoom(z as i8));
+                }
+                false
+            }
+            Msg::OpenPopup(popup) => {
+                self.popup = Some(popup.clone());
+                self.notify(Event::PopupOpen(popup));
+                // pan after popup is drawn and its size is known
+                self.link.send_self(Msg::AutoPan);
+                true
+            }
+            Msg::ClosePopup => {
+                if self.popup.take().is_some() {
+                    self.notify(Event::PopupClose);
+                    true
+                } else {
+                    false
+                }
+            }
+            Msg::AutoPan => {
+                let rects = (
+                    Map::element_rect(&self.id),
+                    Map::element_rect(&self.popup_id()),
+                );
+                if let (Some(map), Some(popup)) = rects {
+                    let offset = Popup::autopan_offset(
+                        (
+                            popup.get_left() - map.get_left(),
+                            popup.get_top() - map.get_top(),
+                        )
+                            .into(),
+                        (popup.get_width(), popup.get_height()).into(),
+                        (map.get_width(), map.get_height()).into(),
+                        POPUP_PADDING,
+                    );
+                    self.center = self
+                        .center
+                        .px(self.zoom)
+                        .translate(&offset.neg())
+                        .lonlat(self.zoom);
+                    offset != Px::default()
+                } else {
+                    false
+                }
+            }
+            Msg::Location(result) => match self.locator.receive(result) {
+                Update::Ignored => false,
+                Update::Found(location, center) => {
+                    if let Some(ll) = center {
+                        self.finish_panning();
+                        self.center = ll;
+                    }
+                    self.notify(Event::LocationFound(location));
+                    true
+                }
+                Update::Failed(e) => {
+                    self.notify(Event::LocationError(e));
+                    true
+                }
+            },
+            Msg::Action(action) => {
+                let z = self.zoom as i8;
+                match action {
+                    Action::ZoomIn => self.link.send_self(Msg::Zoom(z + 1)),
+                    Action::ZoomOut => self.link.send_self(Msg::Zoom(z - 1)),
+                    Action::SelectBaseLayer(idx) => {
+                        let changed = self.layers.select_base(idx);
+                        self.refresh_attributions();
+                        self.notify_layers(&changed);
+                        return !changed.is_empty();
+                    }
+                    Action::ToggleFullscreen => {
+                        // map is redrawn on resize
+                        js! { @(no_return)
+                            var el = document.getElementById(@{&self.id});
+                            if (document.fullscreenElement || document.webkitFullscreenElement) {
+                                (document.exitFullscreen || document.webkitExitFullscreen).call(document);
+                            } else if (el) {
+                                (el.requestFullscreen || el.webkitRequestFullscreen).call(el);
+                            }
+                        }
+                    }
+                    Action::ToggleLocate(follow) => {
+                        let cb = self.link.send_back(Msg::Location);
+                        self.locator.toggle(follow, cb);
+                        return true;
+                    }
+                    Action::ToggleMeasure(kind) => {
+                        self.measure = match self.measure {
+                            Some(ref m) if m.kind == kind => None,
+                            _ => Some(Measurement::new(kind)),
+                        };
+                        return true;
+                    }
+                    Action::CenterOn(ll) => {
+                        self.finish_panning();
+                        self.center = ll;
+                        return true;
+                    }
+                    Action::ToggleLayer(idx) => {
+                        let visible = match self.layers.get(idx) {
+                            Some(layer) => layer.visible,
+                            None => return false,
+                        };
+                        self.layers.set_visible(idx, !visible);
+                        self.refresh_attributions();
+                        self.notify_layers(&[idx]);
+                        return true;
+                    }
+                }
+                false
+            }
+        }
+    }
+
+    fn destroy(&mut self) {
+        self.locator.stop();
+        // resize and escape listeners on window and document outlive the map unless removed
+        for handle in self.handles.drain(..) {
+            handle.remove();
+        }
+        if let Some(observer) = self.resize_observer.take() {
+            js! { @(no_return)
+                var observer = @{observer};
+                observer.observer.disconnect();
+                observer.notify.drop();
+            }
         }
     }
 
     fn view(&self) -> Html<Self> {
         // calc viewports
         let (vw, vw_outer) = self.calc_viewports();
-        // zoomlevel
-        let z = self.zoom as i8;
+        let ctx = control::Context {
+            id: &self.id,
+            vw,
+            zoom: self.zoom,
+            min_zoom: MIN_ZOOM as usize,
+            max_zoom: MAX_ZOOM as usize,
+            layers: self.layers.layers(),
+            attributions: &self.attributions,
+            cursor: self.cursor,
+            fullscreen: self.fullscreen,
+            locating: self.locator.state().active(),
+            measuring: self.measure.as_ref().map(|m| m.kind),
+        };
         // visible layers
         let visible_layers = self.layers.layers_by_visibility(true);
 
         html! {
-            <div id={&self.id}, class="remap-map",>
-                // TODO: abstract
-                <div class="remap-zoom-controls",>
-                    <i class="remap-control remap-control-zoom-in", onclick=|_| Msg::Zoom(z + 1),></i>
-                    <i class="remap-control remap-control-zoom-out", onclick=|_| Msg::Zoom(z - 1),></i>
-                </div>
+            <div id={&self.id}, class=if self.hovered.is_some() { "remap-map remap-feature-hover" } else { "remap-map" },>
+                { control::view(&self.controls, &ctx) }
                 <div class="remap-viewport",>
                     // tile grid
-                    <Grid: vw=vw, vw_outer=vw_outer, layers=visible_layers, />
+                    <Grid: vw=vw, vw_outer=vw_outer, layers=visible_layers, hidpi=self.hidpi, />
+                    { for self.vector_tiles.iter().filter(|l| l.visible).map(|l| html! {
+                        <VectorTiles: vw=vw, vw_outer=vw_outer, layer=l.clone(), />
+                    }) }
+                    // overlays
+                    { for self.geojson.iter().filter(|l| l.visible).map(|l| self.view_vector(l, &vw, &vw_outer)) }
+                    { for self.heatmaps.iter().filter(|l| l.visible).map(|l| html! {
+                        <Heatmap: vw=vw, layer=l.clone(), />
+                    }) }
+                    { if self.marker_renderer == Renderer::Canvas { html! {
+                        <MarkerCanvas: vw=vw, vw_outer=vw_outer, markers=self.markers.clone(),
+                            clusters=self.clusters.clone(), />
+                    } } else { html! {} } }
+                    <Markers: vw=vw, vw_outer=vw_outer, markers=self.markers.clone(), clusters=self.clusters.clone(),
+                        renderer=self.marker_renderer,
+                        onclick=|idx| Msg::MarkerClick(idx),
+                        oncluster=|idx| Msg::ClusterClick(idx), />
+                    { self.view_location(&vw) }
+                    { for self.measure.iter().map(|m| html! {
+                        <Measure: vw=vw, measurement=m.clone(), cursor=self.cursor, />
+                    }) }
+                    <div class="remap-popup-pane",>
+                        { self.view_popup(&vw) }
+                    </div>
                 </div>
                 // input handling component
-                <Input: oninput=|(px,e)| Msg::Input(px,e), />
+                <Input: id=format!("{}_input", self.id), oninput=|(px,e)| Msg::Input(px,e), />
             </div>
         }
     }
@@ -0,0 +1,228 @@
+use super::canvas::context;
+use super::markers::CULL_MARGIN;
+use crate::model::marker::{CLUSTER_RADIUS, MARKER_RADIUS};
+use crate::model::{Bounds, Cluster, Marker, Viewport};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::rc::Rc;
+use stdweb::web::event::ResourceLoadEvent;
+use stdweb::web::html_element::ImageElement;
+use stdweb::web::{
+    CanvasRenderingContext2d, EventListenerHandle, FillRule, IEventTarget, TextAlign, TextBaseline,
+};
+use yew::services::render::{RenderService, RenderTask};
+use yew::{html, Component, ComponentLink, Html, NodeRef, Properties, ShouldRender};
+
+/// Markers and clusters drawn on canvas, markers with html content are left out
+///
+/// Canvas does not receive pointer events, clicks are hit-tested by the map.
+pub struct MarkerCanvas {
+    link: ComponentLink<Self>,
+    // visible viewport
+    vw: Viewport,
+    // area with drawn markers
+    vw_outer: Viewport,
+    markers: Vec<Marker>,
+    // marker clusters on current zoom level, drawn instead of markers if set
+    clusters: Option<Rc<Vec<Cluster>>>,
+    // icon images by url, loaded images trigger redraw
+    icons: HashMap<String, ImageElement>,
+    handles: Vec<EventListenerHandle>,
+    canvas: NodeRef,
+    // render service vars
+    render: RenderService,
+    render_task: Option<RenderTask>,
+}
+
+pub enum Msg {
+    Draw,
+    Loaded,
+}
+
+#[derive(Properties, PartialEq, Clone, Default)]
+pub struct Prop {
+    pub vw: Viewport,
+    pub vw_outer: Viewport,
+    pub markers: Vec<Marker>,
+    pub clusters: Option<Rc<Vec<Cluster>>>,
+}
+
+impl MarkerCanvas {
+    // draws on next animation frame, pending draw is reused
+    fn schedule(&mut self) {
+        if self.render_task.is_none() {
+            let cb = self.link.send_back(|_| Msg::Draw);
+            self.render_task = Some(self.render.request_animation_frame(cb));
+        }
+    }
+
+    fn draw(&self) {
+        let ctx = match context(&self.canvas, &self.vw.size()) {
+            Some(ctx) => ctx,
+            None => return,
+        };
+        let (nw, se) = self.vw_outer.pixel_bounds();
+        let bounds = Bounds::new(nw, se).pad(CULL_MARGIN);
+        let z = self.vw_outer.z;
+        match self.clusters {
+            Some(ref clusters) => {
+                for c in clusters.iter() {
+                    if !bounds.contains(&c.position.px(z)) {
+                        continue;
+                    }
+                    match (c.count(), self.markers.get(c.members[0])) {
+                        (1, Some(m)) => self.marker(&ctx, m),
+                        _ => self.cluster(&ctx, c),
+                    }
+                }
+            }
+            None => {
+                for m in self.markers.iter() {
+                    if bounds.contains(&m.position.px(z)) {
+                        self.marker(&ctx, m);
+                    }
+                }
+            }
+        }
+    }
+
+    // draws marker icon or default circle, html markers are drawn as elements
+    fn marker(&self, ctx: &CanvasRenderingContext2d, marker: &Marker) {
+        if marker.html.is_some() {
+            return;
+        }
+        let origin = self
+            .vw
+            .point_offset(&marker.position)
+            .distance(&marker.anchor);
+        let (x, y) = (origin.x as f64, origin.y as f64);
+        match marker.icon {
+            Some(ref url) => {
+                // icons are drawn once loaded
+                let img = match self.icons.get(url) {
+                    Some(img) if img.complete() => img.clone(),
+                    _ => return,
+                };
+                let drawn = match marker.size {
+                    Some(size) => ctx.draw_image_d(img, x, y, size.x as f64, size.y as f64),
+                    None => ctx.draw_image(img, x, y),
+                };
+                drawn.ok();
+            }
+            None => {
+                circle(ctx, x, y, MARKER_RADIUS, "white");
+                circle(ctx, x, y, MARKER_RADIUS - 2.0, "#3388ff");
+            }
+        }
+    }
+
+    // draws cluster circle with member count
+    fn cluster(&self, ctx: &CanvasRenderingContext2d, cluster: &Cluster) {
+        let p = self.vw.point_offset(&cluster.position);
+        let (x, y) = (p.x as f64, p.y as f64);
+        circle(ctx, x, y, CLUSTER_RADIUS + 5.0, "rgba(51, 136, 255, 0.3)");
+        circle(ctx, x, y, CLUSTER_RADIUS, "rgba(51, 136, 255, 0.8)");
+        ctx.set_fill_style_color("white");
+        ctx.set_font("12px sans-serif");
+        ctx.set_text_align(TextAlign::Center);
+        ctx.set_text_baseline(TextBaseline::Middle);
+        ctx.fill_text(&cluster.count().to_string(), x, y, None);
+    }
+
+    // starts loading icons not loaded yet
+    fn load_icons(&mut self) {
+        for marker in self.markers.iter().filter(|m| m.html.is_none()) {
+            let url = match marker.icon {
+                Some(ref url) if !self.icons.contains_key(url) => url,
+                _ => continue,
+            };
+            let img = ImageElement::new();
+            let cb = self.link.send_back(|_| Msg::Loaded);
+            self.handles
+                .push(img.add_event_listener(move |_: ResourceLoadEvent| cb.emit(())));
+            img.set_src(url);
+            self.icons.insert(url.clone(), img);
+        }
+    }
+}
+
+// fills circle with color
+fn circle(ctx: &CanvasRenderingContext2d, x: f64, y: f64, radius: f64, color: &str) {
+    ctx.begin_path();
+    ctx.arc(x, y, radius, 0.0, 2.0 * PI, false);
+    ctx.set_fill_style_color(color);
+    ctx.fill(FillRule::NonZero);
+}
+
+impl Component for MarkerCanvas {
+    type Message = Msg;
+    type Properties = Prop;
+
+    fn create(prop: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut canvas = MarkerCanvas {
+            link,
+            vw: prop.vw,
+            vw_outer: prop.vw_outer,
+            markers: prop.markers,
+            clusters: prop.clusters,
+            icons: HashMap::new(),
+            handles: vec![],
+            canvas: NodeRef::default(),
+            render: RenderService::new(),
+            render_task: None,
+        };
+        canvas.load_icons();
+        canvas
+    }
+
+    fn mounted(&mut self) -> ShouldRender {
+        self.schedule();
+        false
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Draw => {
+                self.render_task = None;
+                self.draw();
+            }
+            Msg::Loaded => self.schedule(),
+        }
+        false
+    }
+
+    fn change(&mut self, prop: Self::Properties) -> ShouldRender {
+        let changed = self.vw != prop.vw
+            || self.vw_outer != prop.vw_outer
+            || self.markers != prop.markers
+            || self.clusters != prop.clusters;
+        let resized = self.vw.size() != prop.vw.size();
+        let reload = self.markers != prop.markers;
+        self.vw = prop.vw;
+        self.vw_outer = prop.vw_outer;
+        self.markers = prop.markers;
+        self.clusters = prop.clusters;
+        if reload {
+            self.load_icons();
+        }
+        if changed {
+            self.schedule();
+        }
+        // canvas is redrawn directly, element changes only with size
+        resized
+    }
+
+    fn destroy(&mut self) {
+        for handle in self.handles.drain(..) {
+            handle.remove();
+        }
+    }
+
+    fn view(&self) -> Html<Self> {
+        let size = self.vw.size();
+        html! {
+            <canvas class="remap-marker-canvas", ref=self.canvas.clone(),
+                style=format!("width: {}px; height: {}px", size.x, size.y),></canvas>
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use super::raw::RawHtml;
+use crate::model::{Bounds, Cluster, LonLat, Marker, Renderer, Viewport};
+use std::rc::Rc;
+use yew::{html, Callback, Component, ComponentLink, Html, Properties, ShouldRender};
+
+// markers this far outside the outer viewport are still drawn, so that their icons
+// do not pop in at the edges
+pub const CULL_MARGIN: i64 = 256;
+
+/// Marker overlay pane
+pub struct Markers {
+    // visible viewport
+    vw: Viewport,
+    // area with drawn markers
+    vw_outer: Viewport,
+    // markers to draw
+    markers: Vec<Marker>,
+    // marker clusters on current zoom level, drawn instead of markers if set
+    clusters: Option<Rc<Vec<Cluster>>>,
+    // with canvas renderer only html markers are drawn here
+    renderer: Renderer,
+    // click handler, receives marker index
+    onclick: Option<Callback<usize>>,
+    // cluster click handler, receives cluster index
+    oncluster: Option<Callback<usize>>,
+    // html content nodes by marker index
+    html: RawHtml,
+}
+
+pub enum Msg {
+    Click(usize),
+    ClusterClick(usize),
+}
+
+#[derive(Properties, PartialEq, Clone, Default)]
+pub struct Prop {
+    pub vw: Viewport,
+    pub vw_outer: Viewport,
+    pub markers: Vec<Marker>,
+    pub clusters: Option<Rc<Vec<Cluster>>>,
+    pub renderer: Renderer,
+    pub onclick: Option<Callback<usize>>,
+    pub oncluster: Option<Callback<usize>>,
+}
+
+impl Component for Markers {
+    type Message = Msg;
+    type Properties = Prop;
+
+    fn create(prop: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Markers {
+            vw: prop.vw,
+            vw_outer: prop.vw_outer,
+            markers: prop.markers,
+            clusters: prop.clusters,
+            renderer: prop.renderer,
+            onclick: prop.onclick,
+            oncluster: prop.oncluster,
+            html: RawHtml::default(),
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Click(idx) => {
+                if let Some(ref cb) = self.onclick {
+                    cb.emit(idx);
+                }
+            }
+            Msg::ClusterClick(idx) => {
+                if let Some(ref cb) = self.oncluster {
+                    cb.emit(idx);
+                }
+            }
+        }
+        false
+    }
+
+    fn change(&mut self, prop: Self::Properties) -> ShouldRender {
+        let changed = self.vw != prop.vw
+            || self.vw_outer != prop.vw_outer
+            || self.markers != prop.markers
+            || self.clusters != prop.clusters
+            || self.renderer != prop.renderer;
+        self.vw = prop.vw;
+        self.vw_outer = prop.vw_outer;
+        self.markers = prop.markers;
+        let len = self.markers.len();
+        self.html.retain(|i| i < len);
+        self.clusters = prop.clusters;
+        self.renderer = prop.renderer;
+        self.onclick = prop.onclick;
+        self.oncluster = prop.oncluster;
+        changed
+    }
+
+    fn view(&self) -> Html<Self> {
+        match self.clusters {
+            Some(ref clusters) => html! {
+                <div class="remap-marker-pane remap-noselect",>
+                    { for clusters.iter().enumerate()
+                        .filter(|(_, c)| self.in_view(&c.position))
+                        .map(|(i, c)| self.cluster(i, c)) }
+                </div>
+            },
+            None => html! {
+                <div class="remap-marker-pane remap-noselect",>
+                    { for self.markers.iter().enumerate()
+                        .filter(|(_, m)| self.drawn(m) && self.in_view(&m.position))
+                        .map(|(i, m)| self.marker(i, m)) }
+                </div>
+            },
+        }
+    }
+}
+
+impl Markers {
+    // checks if marker is drawn as element, not on canvas
+    fn drawn(&self, marker: &Marker) -> bool {
+        self.renderer == Renderer::Svg || marker.html.is_some()
+    }
+    // checks if point is near enough to outer viewport to be drawn
+    fn in_view(&self, ll: &LonLat) -> bool {
+        let (nw, se) = self.vw_outer.pixel_bounds();
+        Bounds::new(nw, se)
+            .pad(CULL_MARGIN)
+            .contains(&ll.px(self.vw_outer.z))
+    }
+    // draws cluster, single point clusters are drawn as their marker
+    fn cluster(&self, idx: usize, cluster: &Cluster) -> Html<Self> {
+        match (cluster.count(), self.markers.get(cluster.members[0])) {
+            (1, Some(m)) if self.drawn(m) => return self.marker(cluster.members[0], m),
+            (1, Some(_)) => return html! {},
+            _ if self.renderer == Renderer::Canvas => return html! {},
+            _ => (),
+        }
+        let offset = self.vw.point_offset(&cluster.position);
+        let size = match cluster.count() {
+            0..=9 => "small",
+            10..=99 => "medium",
+            _ => "large",
+        };
+        html! {
+            <div class=format!("remap-marker remap-cluster remap-cluster-{}", size),
+                onclick=|_| Msg::ClusterClick(idx),
+                style={format!("transform: translate({}px, {}px)", &offset.x, &offset.y)},>
+                <span>{ cluster.count() }</span>
+            </div>
+        }
+    }
+    // draws marker
+    fn marker(&self, idx: usize, marker: &Marker) -> Html<Self> {
+        let offset = self
+            .vw
+            .point_offset(&marker.position)
+            .distance(&marker.anchor);
+        html! {
+            <div class=format!("remap-marker {}", &marker.class), onclick=|_| Msg::Click(idx),
+                style={format!("transform: translate({}px, {}px)", &offset.x, &offset.y)},>
+                { self.marker_content(idx, marker) }
+            </div>
+        }
+    }
+    // draws marker content: html, icon or default icon
+    fn marker_content(&self, idx: usize, marker: &Marker) -> Html<Self> {
+        match (&marker.html, &marker.icon) {
+            (Some(html), _) => self.html.view(idx, "remap-marker-html", html),
+            (None, Some(icon)) => html! {
+                <img class="remap-marker-icon", src=icon, draggable="false",/>
+            },
+            (None, None) => html! {
+                <span class="remap-marker-default",/>
+            },
+        }
+    }
+}
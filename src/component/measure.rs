@@ -0,0 +1,108 @@
+use crate::model::measure::{format_area, format_distance};
+use crate::model::{LonLat, MeasureKind, Measurement, Px, Viewport};
+use yew::{html, Component, ComponentLink, Html, Properties, ShouldRender};
+
+/// Measurement lines with distance and area labels
+pub struct Measure {
+    vw: Viewport,
+    measurement: Measurement,
+    // rubber band end while measuring
+    cursor: Option<LonLat>,
+}
+
+pub enum Msg {}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct Prop {
+    pub vw: Viewport,
+    #[props(required)]
+    pub measurement: Measurement,
+    pub cursor: Option<LonLat>,
+}
+
+impl Component for Measure {
+    type Message = Msg;
+    type Properties = Prop;
+
+    fn create(prop: Self::Properties, _: ComponentLink<Self>) -> Self {
+        Measure {
+            vw: prop.vw,
+            measurement: prop.measurement,
+            cursor: prop.cursor,
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {}
+    }
+
+    fn change(&mut self, prop: Self::Properties) -> ShouldRender {
+        let changed = self.vw != prop.vw
+            || self.measurement != prop.measurement
+            || self.cursor != prop.cursor;
+        self.vw = prop.vw;
+        self.measurement = prop.measurement;
+        self.cursor = prop.cursor;
+        changed
+    }
+
+    fn view(&self) -> Html<Self> {
+        let m = &self.measurement;
+        let cursor = self.cursor.as_ref();
+        let points: Vec<Px> = m
+            .points(cursor)
+            .iter()
+            .map(|ll| self.vw.point_offset(ll))
+            .collect();
+        if points.is_empty() {
+            return html! {};
+        }
+        let size = self.vw.size();
+        let path = points
+            .iter()
+            .map(|p| format!("{},{}", p.x, p.y))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let shape = match m.kind {
+            MeasureKind::Distance => html! { <polyline points=path, /> },
+            MeasureKind::Area => html! { <polygon points=path, /> },
+        };
+        // segment lengths at segment midpoints, closing segment of polygons included
+        let mut ends = points.clone();
+        if m.kind == MeasureKind::Area && ends.len() > 2 {
+            ends.push(ends[0]);
+        }
+        let segments = m.segments(cursor);
+        let total = match m.kind {
+            MeasureKind::Distance => format_distance(m.distance(cursor)),
+            MeasureKind::Area => format!(
+                "{}, {}",
+                format_area(m.area(cursor)),
+                format_distance(m.distance(cursor))
+            ),
+        };
+        let last = points[points.len() - 1];
+        html! {
+            <div class="remap-measure",>
+                <svg class="remap-measure-shape", width=size.x, height=size.y,>
+                    { shape }
+                    { for points.iter().map(|p| html! { <circle cx=p.x, cy=p.y, r=4, /> }) }
+                </svg>
+                { for ends.windows(2).zip(segments.iter()).map(|(w, d)| {
+                    label(&w[0].avg(&w[1]), &format_distance(*d), "remap-measure-segment")
+                }) }
+                { label(&last, &total, "remap-measure-total") }
+            </div>
+        }
+    }
+}
+
+// draws text label at pixel position
+fn label(p: &Px, text: &str, class: &str) -> Html<Measure> {
+    html! {
+        <div class=format!("remap-measure-label {}", class),
+            style=format!("transform: translate({}px, {}px)", p.x, p.y),>
+            { text }
+        </div>
+    }
+}
@@ -1,9 +1,27 @@
+mod canvas;
+pub mod control;
+mod geojson;
+pub mod geolocation;
 mod grid;
+mod heatmap;
 mod input;
 pub mod map;
+mod marker_canvas;
+mod markers;
+mod measure;
+mod raw;
 mod tile;
+mod vector_tiles;
 
+pub use self::canvas::Canvas;
+pub use self::geojson::GeoJson;
+pub use self::geolocation::{BrowserGeolocation, GeolocationProvider, Locator, MockGeolocation};
 pub use self::grid::Grid;
+pub use self::heatmap::Heatmap;
 pub use self::input::{Input, InputEvent};
 pub use self::map::Map;
+pub use self::marker_canvas::MarkerCanvas;
+pub use self::markers::Markers;
+pub use self::measure::Measure;
 pub use self::tile::Tile;
+pub use self::vector_tiles::VectorTiles;
@@ -0,0 +1,44 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use stdweb::web::{document, Element, IElement};
+use yew::virtual_dom::VNode;
+use yew::{Component, Html};
+
+// creates div with given class and inner html
+fn element(class: &str, html: &str) -> Element {
+    let el = document()
+        .create_element("div")
+        .expect("failed to create element");
+    el.set_attribute("class", class).ok();
+    js! { @(no_return) @{&el}.innerHTML = @{html}; }
+    el
+}
+
+/// Raw html nodes reused between renders while their html stays the same,
+/// so that content is not parsed again on every frame
+///
+/// The html is inserted unsanitized, it must come from a trusted source.
+#[derive(Default)]
+pub struct RawHtml {
+    // node and its html by key
+    nodes: RefCell<HashMap<usize, (String, Element)>>,
+}
+
+impl RawHtml {
+    /// Renders html inside a div with given class, reusing the node of key if html is unchanged
+    pub fn view<C: Component>(&self, key: usize, class: &str, html: &str) -> Html<C> {
+        let mut nodes = self.nodes.borrow_mut();
+        match nodes.get(&key) {
+            Some((cached, el)) if cached == html => VNode::VRef(el.clone().into()),
+            _ => {
+                let el = element(class, html);
+                nodes.insert(key, (html.into(), el.clone()));
+                VNode::VRef(el.into())
+            }
+        }
+    }
+    /// Drops nodes of keys not matching predicate
+    pub fn retain<F: Fn(usize) -> bool>(&self, keep: F) {
+        self.nodes.borrow_mut().retain(|k, _| keep(*k));
+    }
+}
@@ -4,6 +4,10 @@ use yew::{html, Component, ComponentLink, Html, Properties, ShouldRender};
 pub struct Tile {
     tile: TileModel,
     layer: TileLayer,
+    // drawn size in pixels
+    size: i64,
+    // high-DPI screen
+    hidpi: bool,
 }
 
 pub enum Msg {}
@@ -12,6 +16,8 @@ pub enum Msg {}
 pub struct Prop {
     pub tile: TileModel,
     pub layer: TileLayer,
+    pub size: i64,
+    pub hidpi: bool,
 }
 
 impl Component for Tile {
@@ -22,6 +28,8 @@ impl Component for Tile {
         Tile {
             tile: prop.tile,
             layer: prop.layer,
+            size: prop.size,
+            hidpi: prop.hidpi,
         }
     }
 
@@ -32,14 +40,24 @@ impl Component for Tile {
     }
 
     fn change(&mut self, props: Self::Properties) -> ShouldRender {
-        let c = self.tile != props.tile;
+        let c = self.tile != props.tile
+            || self.size != props.size
+            || self.hidpi != props.hidpi
+            || self.layer != props.layer;
         self.tile = props.tile;
+        self.layer = props.layer;
+        self.size = props.size;
+        self.hidpi = props.hidpi;
         c
     }
 
     fn view(&self) -> Html<Self> {
         html! {
-            <span class="remap-tile", style={&format!("background-image: url({})",&self.layer.tile_url(&self.tile))}, />
+            <span class="remap-tile", style={&format!(
+                "background-image: url({}); width: {size}px; height: {size}px",
+                &self.layer.tile_url(&self.tile, self.hidpi),
+                size = self.size,
+            )}, />
         }
     }
 }
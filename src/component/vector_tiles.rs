@@ -0,0 +1,197 @@
+use super::canvas::{context, set_style, shape};
+use crate::model::{Tile, VectorTile, VectorTileLayer, Viewport};
+use std::borrow::Cow;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::rc::Rc;
+use yew::format::{Binary, Nothing};
+use yew::services::fetch::{FetchService, FetchTask, Request, Response};
+use yew::services::render::{RenderService, RenderTask};
+use yew::{html, Component, ComponentLink, Html, NodeRef, Properties, ShouldRender};
+
+/// Vector tile layer drawn on canvas on top of tile grid
+pub struct VectorTiles {
+    link: ComponentLink<Self>,
+    // visible viewport
+    vw: Viewport,
+    // viewport tiles are loaded for
+    vw_outer: Viewport,
+    layer: VectorTileLayer,
+    // tiles of current view by state
+    tiles: HashMap<Tile, TileState>,
+    fetch: FetchService,
+    canvas: NodeRef,
+    // render service vars
+    render: RenderService,
+    render_task: Option<RenderTask>,
+}
+
+enum TileState {
+    // request is cancelled when task is dropped
+    #[allow(dead_code)]
+    Loading(FetchTask),
+    Loaded(Rc<VectorTile>),
+    // not retried until tile leaves view
+    Failed,
+}
+
+pub enum Msg {
+    Loaded(Tile, Vec<u8>),
+    Failed(Tile),
+    Draw,
+}
+
+#[derive(Properties, PartialEq, Clone)]
+pub struct Prop {
+    pub vw: Viewport,
+    pub vw_outer: Viewport,
+    #[props(required)]
+    pub layer: VectorTileLayer,
+}
+
+impl VectorTiles {
+    // fetches missing tiles of current view and drops the others
+    fn load(&mut self) {
+        let z = self.layer.source.source_zoom(self.vw.z, false);
+        let tiles: Vec<Tile> = self.vw_outer.tiles_at(z).collect();
+        self.tiles.retain(|t, _| tiles.contains(t));
+        for tile in tiles {
+            if let Entry::Vacant(entry) = self.tiles.entry(tile) {
+                let request = Request::get(self.layer.source.tile_url(&tile, false))
+                    .body(Nothing)
+                    .expect("valid tile request");
+                let cb = self.link.send_back(move |response: Response<Binary>| {
+                    let (meta, body) = response.into_parts();
+                    match body {
+                        Ok(data) if meta.status.is_success() => Msg::Loaded(tile, data),
+                        _ => Msg::Failed(tile),
+                    }
+                });
+                let task = self.fetch.fetch_binary(request, cb);
+                entry.insert(TileState::Loading(task));
+            }
+        }
+    }
+
+    // draws on next animation frame, pending draw is reused
+    fn schedule(&mut self) {
+        if self.render_task.is_none() {
+            let cb = self.link.send_back(|_| Msg::Draw);
+            self.render_task = Some(self.render.request_animation_frame(cb));
+        }
+    }
+
+    fn draw(&self) {
+        let ctx = match context(&self.canvas, &self.vw.size()) {
+            Some(ctx) => ctx,
+            None => return,
+        };
+        let loaded = self.tiles.iter().filter_map(|(tile, state)| match state {
+            TileState::Loaded(data) => Some((tile, data)),
+            _ => None,
+        });
+        let z = self.vw.z as f64;
+        for (tile, data) in loaded {
+            let origin = self.vw.pixel_offset(tile);
+            let size = tile.size_at(self.vw.z) as f64;
+            for rule in self.layer.rules.iter() {
+                if let Some(layer) = data.layer(&rule.layer) {
+                    let scale = size / f64::from(layer.extent.max(1));
+                    if rule.style_spec.is_none() {
+                        set_style(&ctx, &rule.style);
+                    }
+                    for f in layer.features.iter() {
+                        let style = match &rule.style_spec {
+                            Some(spec) => {
+                                let style = spec.resolve(&rule.style, z, Some(&f.properties));
+                                set_style(&ctx, &style);
+                                Cow::Owned(style)
+                            }
+                            None => Cow::Borrowed(&rule.style),
+                        };
+                        for s in f.shapes(&origin, scale) {
+                            shape(&ctx, &s, &style);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Component for VectorTiles {
+    type Message = Msg;
+    type Properties = Prop;
+
+    fn create(prop: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut tiles = VectorTiles {
+            link,
+            vw: prop.vw,
+            vw_outer: prop.vw_outer,
+            layer: prop.layer,
+            tiles: HashMap::new(),
+            fetch: FetchService::new(),
+            canvas: NodeRef::default(),
+            render: RenderService::new(),
+            render_task: None,
+        };
+        tiles.load();
+        tiles
+    }
+
+    fn mounted(&mut self) -> ShouldRender {
+        self.schedule();
+        false
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Loaded(tile, data) => {
+                // tile may have left view while loading
+                if let Some(state) = self.tiles.get_mut(&tile) {
+                    *state = match VectorTile::decode(&data) {
+                        Ok(vt) => TileState::Loaded(Rc::new(vt)),
+                        Err(_) => TileState::Failed,
+                    };
+                    self.schedule();
+                }
+            }
+            Msg::Failed(tile) => {
+                if let Some(state) = self.tiles.get_mut(&tile) {
+                    *state = TileState::Failed;
+                }
+            }
+            Msg::Draw => {
+                self.render_task = None;
+                self.draw();
+            }
+        }
+        false
+    }
+
+    fn change(&mut self, prop: Self::Properties) -> ShouldRender {
+        let resized = self.vw.size() != prop.vw.size();
+        if self.layer.source != prop.layer.source {
+            self.tiles.clear();
+        }
+        let changed =
+            self.vw != prop.vw || self.vw_outer != prop.vw_outer || self.layer != prop.layer;
+        self.vw = prop.vw;
+        self.vw_outer = prop.vw_outer;
+        self.layer = prop.layer;
+        if changed {
+            self.load();
+            self.schedule();
+        }
+        // canvas is redrawn directly, element changes only with size
+        resized
+    }
+
+    fn view(&self) -> Html<Self> {
+        let size = self.vw.size();
+        html! {
+            <canvas class="remap-vector-layer", ref=self.canvas.clone(),
+                style=format!("width: {}px; height: {}px", size.x, size.y),></canvas>
+        }
+    }
+}
@@ -1,4 +1,4 @@
-#![recursion_limit = "256"]
+#![recursion_limit = "512"]
 
 #[macro_use]
 extern crate stdweb;
@@ -7,7 +7,7 @@ extern crate stdweb;
 extern crate itertools;
 
 pub mod component;
-mod model;
+pub mod model;
 mod state;
 
 use component::Map;
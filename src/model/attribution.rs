@@ -0,0 +1,36 @@
+use super::{GeoJsonLayer, HeatmapLayer, TileLayer, VectorTileLayer};
+
+/// Collects attributions of visible layers in drawing order, skipping empty and duplicate ones
+///
+/// Attributions are html, the attribution control inserts them unsanitized.
+pub fn attributions(
+    tiles: &[TileLayer],
+    vector_tiles: &[VectorTileLayer],
+    geojson: &[GeoJsonLayer],
+    heatmaps: &[HeatmapLayer],
+) -> Vec<String> {
+    let all = tiles
+        .iter()
+        .filter(|l| l.visible)
+        .map(|l| &l.attribution)
+        .chain(
+            vector_tiles
+                .iter()
+                .filter(|l| l.visible)
+                .map(|l| &l.source.attribution),
+        )
+        .chain(geojson.iter().filter(|l| l.visible).map(|l| &l.attribution))
+        .chain(
+            heatmaps
+                .iter()
+                .filter(|l| l.visible)
+                .map(|l| &l.attribution),
+        );
+    let mut attributions: Vec<String> = vec![];
+    for a in all.flatten() {
+        if !a.is_empty() && !attributions.contains(a) {
+            attributions.push(a.clone());
+        }
+    }
+    attributions
+}
@@ -0,0 +1,146 @@
+use super::Px;
+
+/// Pixel rectangle used for clipping
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct Bounds {
+    /// Top-left corner
+    pub min: Px,
+    /// Bottom-right corner
+    pub max: Px,
+}
+
+impl Bounds {
+    /// Creates bounds from corners
+    pub fn new(min: Px, max: Px) -> Self {
+        Self { min, max }
+    }
+
+    /// Grows bounds by `d` pixels in every direction
+    pub fn pad(&self, d: i64) -> Self {
+        Self {
+            min: self.min.translate(&(-d, -d).into()),
+            max: self.max.translate(&(d, d).into()),
+        }
+    }
+
+    /// Checks if point is inside bounds, edges included
+    pub fn contains(&self, p: &Px) -> bool {
+        p.x >= self.min.x && p.x <= self.max.x && p.y >= self.min.y && p.y <= self.max.y
+    }
+
+    /// Clips line to bounds, returns the parts inside
+    pub fn clip_line(&self, points: &[Px]) -> Vec<Vec<Px>> {
+        let mut parts: Vec<Vec<Px>> = vec![];
+        let mut part: Vec<Px> = vec![];
+        for w in points.windows(2) {
+            match self.clip_segment(&w[0], &w[1]) {
+                Some((a, b)) => {
+                    if part.last() != Some(&a) {
+                        // segment entered from outside, start new part
+                        if part.len() > 1 {
+                            parts.push(part);
+                        }
+                        part = vec![a];
+                    }
+                    part.push(b);
+                }
+                None => {
+                    if part.len() > 1 {
+                        parts.push(part);
+                    }
+                    part = vec![];
+                }
+            }
+        }
+        if part.len() > 1 {
+            parts.push(part);
+        }
+        parts
+    }
+
+    // Liang–Barsky segment clipping
+    fn clip_segment(&self, a: &Px, b: &Px) -> Option<(Px, Px)> {
+        let (dx, dy) = ((b.x - a.x) as f64, (b.y - a.y) as f64);
+        let (mut t0, mut t1) = (0.0, 1.0);
+        let checks = [
+            (-dx, (a.x - self.min.x) as f64),
+            (dx, (self.max.x - a.x) as f64),
+            (-dy, (a.y - self.min.y) as f64),
+            (dy, (self.max.y - a.y) as f64),
+        ];
+        for &(p, q) in &checks {
+            if p == 0.0 {
+                if q < 0.0 {
+                    return None;
+                }
+            } else {
+                let r = q / p;
+                if p < 0.0 {
+                    t0 = r.max(t0);
+                } else {
+                    t1 = r.min(t1);
+                }
+            }
+        }
+        if t0 > t1 {
+            return None;
+        }
+        let at = |t: f64| -> Px {
+            (a.x + (t * dx).round() as i64, a.y + (t * dy).round() as i64).into()
+        };
+        Some((at(t0), at(t1)))
+    }
+
+    /// Clips polygon ring to bounds (Sutherland–Hodgman), returned ring is open
+    pub fn clip_ring(&self, ring: &[Px]) -> Vec<Px> {
+        let mut out = ring.to_vec();
+        // drop closing point, edges are walked cyclically
+        if out.len() > 1 && out.first() == out.last() {
+            out.pop();
+        }
+        let (min, max) = (self.min, self.max);
+        out = clip_edge(out, |p| p.x >= min.x, |a, b| intersect_x(a, b, min.x));
+        out = clip_edge(out, |p| p.x <= max.x, |a, b| intersect_x(a, b, max.x));
+        out = clip_edge(out, |p| p.y >= min.y, |a, b| intersect_y(a, b, min.y));
+        out = clip_edge(out, |p| p.y <= max.y, |a, b| intersect_y(a, b, max.y));
+        out
+    }
+}
+
+// clips open ring against single edge
+fn clip_edge<I, X>(ring: Vec<Px>, inside: I, intersect: X) -> Vec<Px>
+where
+    I: Fn(&Px) -> bool,
+    X: Fn(&Px, &Px) -> Px,
+{
+    let mut out = Vec::with_capacity(ring.len());
+    let mut prev = match ring.last() {
+        Some(p) => *p,
+        None => return out,
+    };
+    for p in ring {
+        match (inside(&p), inside(&prev)) {
+            (true, true) => out.push(p),
+            (true, false) => {
+                out.push(intersect(&prev, &p));
+                out.push(p);
+            }
+            (false, true) => out.push(intersect(&prev, &p)),
+            (false, false) => {}
+        }
+        prev = p;
+    }
+    out
+}
+
+// intersection of segment a-b with vertical line at x
+fn intersect_x(a: &Px, b: &Px, x: i64) -> Px {
+    let t = (x - a.x) as f64 / (b.x - a.x) as f64;
+    (x, a.y + (t * (b.y - a.y) as f64).round() as i64).into()
+}
+
+// intersection of segment a-b with horizontal line at y
+fn intersect_y(a: &Px, b: &Px, y: i64) -> Px {
+    let t = (y - a.y) as f64 / (b.y - a.y) as f64;
+    (a.x + (t * (b.x - a.x) as f64).round() as i64, y).into()
+}
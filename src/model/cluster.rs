@@ -0,0 +1,133 @@
+use super::{LonLat, Px};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Point clustering options
+#[derive(Debug, PartialEq, Clone)]
+pub struct Clustering {
+    /// Cluster radius in pixels
+    pub radius: u32,
+    /// Points are not clustered above this zoom level
+    pub max_zoom: usize,
+}
+
+impl Default for Clustering {
+    fn default() -> Self {
+        Self {
+            radius: 60,
+            max_zoom: 16,
+        }
+    }
+}
+
+/// Group of nearby points
+#[derive(Debug, PartialEq, Clone)]
+pub struct Cluster {
+    /// Average position of clustered points
+    pub position: LonLat,
+    /// Indices of clustered points
+    pub members: Vec<usize>,
+    /// Bounding box corners of clustered points
+    pub nw: LonLat,
+    pub se: LonLat,
+}
+
+impl Cluster {
+    // creates cluster of single point
+    fn point(idx: usize, position: LonLat) -> Self {
+        Self {
+            position,
+            members: vec![idx],
+            nw: position,
+            se: position,
+        }
+    }
+
+    /// Number of clustered points
+    pub fn count(&self) -> usize {
+        self.members.len()
+    }
+
+    // merges other cluster into this one, position is weighted by point count
+    fn merge(&mut self, other: &Cluster) {
+        let (a, b) = (self.count() as f64, other.count() as f64);
+        self.position = LonLat {
+            lon: (self.position.lon * a + other.position.lon * b) / (a + b),
+            lat: (self.position.lat * a + other.position.lat * b) / (a + b),
+        };
+        self.members.extend_from_slice(&other.members);
+        self.nw = LonLat {
+            lon: self.nw.lon.min(other.nw.lon),
+            lat: self.nw.lat.max(other.nw.lat),
+        };
+        self.se = LonLat {
+            lon: self.se.lon.max(other.se.lon),
+            lat: self.se.lat.min(other.se.lat),
+        };
+    }
+}
+
+/// Hierarchical greedy clustering of points on all zoom levels
+#[derive(Debug, Default, Clone)]
+pub struct ClusterIndex {
+    // clusters by zoom level, last level holds unclustered points
+    levels: Vec<Rc<Vec<Cluster>>>,
+}
+
+impl ClusterIndex {
+    /// Clusters points, each level is built from clusters of the level above
+    pub fn new(points: &[LonLat], options: &Clustering) -> Self {
+        let points: Vec<Cluster> = points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| Cluster::point(i, *p))
+            .collect();
+        let mut levels = vec![Rc::new(points)];
+        for z in (0..=options.max_zoom).rev() {
+            let level = Self::cluster(&levels[0], z, i64::from(options.radius.max(1)));
+            levels.insert(0, Rc::new(level));
+        }
+        Self { levels }
+    }
+
+    /// Returns clusters on zoom level `z`
+    pub fn clusters(&self, z: usize) -> Rc<Vec<Cluster>> {
+        match self.levels.get(z).or_else(|| self.levels.last()) {
+            Some(level) => level.clone(),
+            None => Rc::new(vec![]),
+        }
+    }
+
+    // greedily merges clusters within radius on zoom level `z`
+    fn cluster(items: &[Cluster], z: usize, radius: i64) -> Vec<Cluster> {
+        let px: Vec<Px> = items.iter().map(|c| c.position.px(z)).collect();
+        // grid with cell size of radius, neighbours are found in adjacent cells
+        let cell = |p: &Px| (p.x.div_euclid(radius), p.y.div_euclid(radius));
+        let mut grid: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+        for (i, p) in px.iter().enumerate() {
+            grid.entry(cell(p)).or_default().push(i);
+        }
+
+        let mut visited = vec![false; items.len()];
+        let mut clusters = vec![];
+        for (i, p) in px.iter().enumerate() {
+            if visited[i] {
+                continue;
+            }
+            visited[i] = true;
+            let mut cluster = items[i].clone();
+            let (cx, cy) = cell(p);
+            for (dx, dy) in iproduct!(-1..=1, -1..=1) {
+                for &j in grid.get(&(cx + dx, cy + dy)).into_iter().flatten() {
+                    let d = px[j].distance(p);
+                    if !visited[j] && d.x * d.x + d.y * d.y <= radius * radius {
+                        visited[j] = true;
+                        cluster.merge(&items[j]);
+                    }
+                }
+            }
+            clusters.push(cluster);
+        }
+        clusters
+    }
+}
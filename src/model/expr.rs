@@ -0,0 +1,264 @@
+use super::StyleError;
+use serde_json::{Map, Value};
+
+/// Value type expected from expression
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Kind {
+    /// Css color string
+    Color,
+    Number,
+    /// Array of numbers
+    Numbers,
+    /// Input of match, interpolate or step, not checked
+    Any,
+}
+
+/// Style expression, evaluated per feature and zoom level
+#[derive(Debug, PartialEq, Clone)]
+pub enum Expr {
+    Literal(Value),
+    /// `["get", key]`, feature property
+    Get(String),
+    /// `["zoom"]`, current zoom level
+    Zoom,
+    /// `["match", input, label, output, ..., fallback]`, labels may be arrays of values
+    Match {
+        input: Box<Expr>,
+        cases: Vec<(Vec<Value>, Expr)>,
+        fallback: Box<Expr>,
+    },
+    /// `["interpolate", ["linear"] | ["exponential", base], input, stop, output, ...]`
+    Interpolate {
+        base: f64,
+        input: Box<Expr>,
+        stops: Vec<(f64, Expr)>,
+    },
+    /// `["step", input, output, stop, output, ...]`
+    Step {
+        input: Box<Expr>,
+        first: Box<Expr>,
+        stops: Vec<(f64, Expr)>,
+    },
+}
+
+// invalid expression error at path
+fn invalid<T>(path: &str, msg: &str) -> Result<T, StyleError> {
+    Err(StyleError::Invalid(path.into(), msg.into()))
+}
+
+// match label equality, numbers are equal by value regardless of integer or float form
+fn same_label(label: &Value, input: &Value) -> bool {
+    match (label.as_f64(), input.as_f64()) {
+        (Some(a), Some(b)) => a == b,
+        _ => label == input,
+    }
+}
+
+// parses stop inputs and outputs, stops must be ascending numbers
+fn parse_stops(args: &[Value], kind: Kind, path: &str) -> Result<Vec<(f64, Expr)>, StyleError> {
+    if args.is_empty() || args.len() % 2 == 1 {
+        return invalid(path, "expected pairs of stop input and output");
+    }
+    let mut stops: Vec<(f64, Expr)> = vec![];
+    for (i, pair) in args.chunks(2).enumerate() {
+        let stop = match pair[0].as_f64() {
+            Some(stop) => stop,
+            None => return invalid(path, &format!("stop {} input must be a number", i)),
+        };
+        match stops.last() {
+            Some((last, _)) if *last >= stop => {
+                return invalid(path, "stop inputs must be in ascending order")
+            }
+            _ => (),
+        }
+        let output = Expr::parse(&pair[1], kind, &format!("{}[stop {}]", path, i))?;
+        stops.push((stop, output));
+    }
+    Ok(stops)
+}
+
+impl Expr {
+    /// Parses expression from json, checking that outputs are of given kind
+    pub fn parse(value: &Value, kind: Kind, path: &str) -> Result<Self, StyleError> {
+        let args = match value {
+            Value::Array(args) => args,
+            v => return Self::literal(v, kind, path),
+        };
+        let op = match args.first() {
+            Some(Value::String(op)) => op.as_str(),
+            // plain arrays of numbers are literals
+            Some(Value::Number(_)) if kind == Kind::Numbers || kind == Kind::Any => {
+                return Self::literal(value, kind, path)
+            }
+            _ => return invalid(path, "expected expression name as first array item"),
+        };
+        let args = &args[1..];
+        match op {
+            "literal" => match args {
+                [v] => Self::literal(v, kind, path),
+                _ => invalid(path, "'literal' expects one argument"),
+            },
+            "get" => match args {
+                [Value::String(key)] => Ok(Expr::Get(key.clone())),
+                _ => invalid(path, "'get' expects property name"),
+            },
+            "zoom" if args.is_empty() => Ok(Expr::Zoom),
+            "zoom" => invalid(path, "'zoom' expects no arguments"),
+            "match" => {
+                if args.len() < 4 || args.len() % 2 == 1 {
+                    return invalid(
+                        path,
+                        "'match' expects input, label and output pairs and fallback",
+                    );
+                }
+                let input = Self::parse(&args[0], Kind::Any, &format!("{}[input]", path))?;
+                let mut cases = vec![];
+                for (i, pair) in args[1..args.len() - 1].chunks(2).enumerate() {
+                    let labels = match &pair[0] {
+                        Value::Array(labels) => labels.clone(),
+                        label => vec![label.clone()],
+                    };
+                    let output = Self::parse(&pair[1], kind, &format!("{}[case {}]", path, i))?;
+                    cases.push((labels, output));
+                }
+                let fallback = args.last().expect("match has fallback");
+                let fallback = Self::parse(fallback, kind, &format!("{}[fallback]", path))?;
+                Ok(Expr::Match {
+                    input: Box::new(input),
+                    cases,
+                    fallback: Box::new(fallback),
+                })
+            }
+            "interpolate" => {
+                if kind != Kind::Number && kind != Kind::Any {
+                    return invalid(path, "'interpolate' can only be used for numbers");
+                }
+                if args.len() < 4 {
+                    return invalid(path, "'interpolate' expects type, input and stops");
+                }
+                let base = match args[0].as_array().map(|a| a.as_slice()) {
+                    Some([Value::String(t)]) if t == "linear" => 1.0,
+                    Some([Value::String(t), base]) if t == "exponential" => match base.as_f64() {
+                        Some(base) if base > 0.0 => base,
+                        _ => return invalid(path, "exponential base must be a positive number"),
+                    },
+                    _ => {
+                        return invalid(
+                            path,
+                            "interpolation type must be [\"linear\"] or [\"exponential\", base]",
+                        )
+                    }
+                };
+                let input = Self::parse(&args[1], Kind::Any, &format!("{}[input]", path))?;
+                Ok(Expr::Interpolate {
+                    base,
+                    input: Box::new(input),
+                    stops: parse_stops(&args[2..], Kind::Number, path)?,
+                })
+            }
+            "step" => {
+                if args.len() < 2 {
+                    return invalid(path, "'step' expects input, output and stops");
+                }
+                let input = Self::parse(&args[0], Kind::Any, &format!("{}[input]", path))?;
+                let first = Self::parse(&args[1], kind, &format!("{}[output]", path))?;
+                let stops = if args.len() > 2 {
+                    parse_stops(&args[2..], kind, path)?
+                } else {
+                    vec![]
+                };
+                Ok(Expr::Step {
+                    input: Box::new(input),
+                    first: Box::new(first),
+                    stops,
+                })
+            }
+            op => invalid(path, &format!("unknown expression '{}'", op)),
+        }
+    }
+
+    // checks literal type
+    fn literal(value: &Value, kind: Kind, path: &str) -> Result<Self, StyleError> {
+        let valid = match kind {
+            Kind::Color => value.is_string(),
+            Kind::Number => value.is_number(),
+            Kind::Numbers => match value {
+                Value::Array(a) => a.iter().all(Value::is_number),
+                _ => false,
+            },
+            Kind::Any => true,
+        };
+        if valid {
+            Ok(Expr::Literal(value.clone()))
+        } else {
+            let expected = match kind {
+                Kind::Color => "color string",
+                Kind::Number => "number",
+                _ => "array of numbers",
+            };
+            invalid(path, &format!("expected {}, got {}", expected, value))
+        }
+    }
+
+    /// Evaluates expression on zoom level with feature properties
+    pub fn eval(&self, zoom: f64, properties: Option<&Map<String, Value>>) -> Value {
+        match self {
+            Expr::Literal(v) => v.clone(),
+            Expr::Get(key) => properties
+                .and_then(|p| p.get(key))
+                .cloned()
+                .unwrap_or(Value::Null),
+            Expr::Zoom => zoom.into(),
+            Expr::Match {
+                input,
+                cases,
+                fallback,
+            } => {
+                let input = input.eval(zoom, properties);
+                cases
+                    .iter()
+                    .find(|(labels, _)| labels.iter().any(|label| same_label(label, &input)))
+                    .map_or(&**fallback, |(_, output)| output)
+                    .eval(zoom, properties)
+            }
+            Expr::Interpolate { base, input, stops } => {
+                let x = match input.eval(zoom, properties).as_f64() {
+                    Some(x) => x,
+                    None => return Value::Null,
+                };
+                let number = |e: &Expr| e.eval(zoom, properties).as_f64();
+                let i = stops.iter().position(|(stop, _)| *stop > x);
+                let value = match i {
+                    Some(0) => number(&stops[0].1),
+                    None => number(&stops[stops.len() - 1].1),
+                    Some(i) => {
+                        let ((z0, a), (z1, b)) = (&stops[i - 1], &stops[i]);
+                        let t = if (*base - 1.0).abs() < 1e-9 {
+                            (x - z0) / (z1 - z0)
+                        } else {
+                            (base.powf(x - z0) - 1.0) / (base.powf(z1 - z0) - 1.0)
+                        };
+                        number(a).and_then(|a| number(b).map(|b| a + (b - a) * t))
+                    }
+                };
+                value.map_or(Value::Null, Value::from)
+            }
+            Expr::Step {
+                input,
+                first,
+                stops,
+            } => {
+                let x = match input.eval(zoom, properties).as_f64() {
+                    Some(x) => x,
+                    None => return Value::Null,
+                };
+                stops
+                    .iter()
+                    .rev()
+                    .find(|(stop, _)| x >= *stop)
+                    .map_or(&**first, |(_, output)| output)
+                    .eval(zoom, properties)
+            }
+        }
+    }
+}
@@ -0,0 +1,387 @@
+use super::simplify::segment_dist_sq;
+use super::{Bounds, LonLat, Px, Simplify, Style, StyleSpec, Viewport};
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde_derive::Deserialize;
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+/// GeoJSON geometry
+/// https://tools.ietf.org/html/rfc7946#section-3.1
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+#[serde(tag = "type")]
+pub enum Geometry {
+    Point {
+        coordinates: LonLat,
+    },
+    MultiPoint {
+        coordinates: Vec<LonLat>,
+    },
+    LineString {
+        coordinates: Vec<LonLat>,
+    },
+    MultiLineString {
+        coordinates: Vec<Vec<LonLat>>,
+    },
+    /// Exterior ring followed by holes
+    Polygon {
+        coordinates: Vec<Vec<LonLat>>,
+    },
+    MultiPolygon {
+        coordinates: Vec<Vec<Vec<LonLat>>>,
+    },
+    GeometryCollection {
+        geometries: Vec<Geometry>,
+    },
+}
+
+/// Geometry projected to pixels
+#[derive(Debug, PartialEq, Clone)]
+pub enum Shape {
+    Point(Px),
+    Line(Vec<Px>),
+    /// Exterior ring followed by holes
+    Polygon(Vec<Vec<Px>>),
+}
+
+impl Shape {
+    /// Moves shape by pixel offset
+    pub fn translate(&self, offset: &Px) -> Shape {
+        let line = |l: &Vec<Px>| l.iter().map(|p| p.translate(offset)).collect();
+        match self {
+            Shape::Point(p) => Shape::Point(p.translate(offset)),
+            Shape::Line(l) => Shape::Line(line(l)),
+            Shape::Polygon(rings) => Shape::Polygon(rings.iter().map(line).collect()),
+        }
+    }
+
+    /// Simplifies lines and rings, returns none if shape collapses
+    pub fn simplify(&self, simplify: &Simplify) -> Option<Shape> {
+        match self {
+            Shape::Point(p) => Some(Shape::Point(*p)),
+            Shape::Line(l) => {
+                let l = simplify.apply(l);
+                if l.len() > 1 {
+                    Some(Shape::Line(l))
+                } else {
+                    None
+                }
+            }
+            Shape::Polygon(rings) => {
+                // closed rings need at least 4 points to have area
+                let mut rings = rings.iter().map(|r| simplify.apply(r));
+                match rings.next() {
+                    Some(exterior) if exterior.len() > 3 => Some(Shape::Polygon(
+                        Some(exterior)
+                            .into_iter()
+                            .chain(rings.filter(|r| r.len() > 3))
+                            .collect(),
+                    )),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// Clips shape to bounds, clipped rings are left open
+    pub fn clip(&self, bounds: &Bounds) -> Vec<Shape> {
+        match self {
+            Shape::Point(p) if bounds.contains(p) => vec![Shape::Point(*p)],
+            Shape::Point(_) => vec![],
+            Shape::Line(l) => bounds.clip_line(l).into_iter().map(Shape::Line).collect(),
+            Shape::Polygon(rings) => {
+                let mut rings = rings.iter().map(|r| bounds.clip_ring(r));
+                match rings.next() {
+                    Some(exterior) if exterior.len() > 2 => vec![Shape::Polygon(
+                        Some(exterior)
+                            .into_iter()
+                            .chain(rings.filter(|r| r.len() > 2))
+                            .collect(),
+                    )],
+                    _ => vec![],
+                }
+            }
+        }
+    }
+}
+
+impl Shape {
+    /// Checks if point is on shape, `radius` is point radius and half of line width
+    pub fn hit(&self, p: &Px, radius: f64, tolerance: f64) -> bool {
+        let d = radius + tolerance;
+        let near_line = |line: &[Px]| {
+            line.windows(2)
+                .any(|w| segment_dist_sq(p, &w[0], &w[1]) <= d * d)
+        };
+        match self {
+            Shape::Point(c) => {
+                let (dx, dy) = ((p.x - c.x) as f64, (p.y - c.y) as f64);
+                dx * dx + dy * dy <= d * d
+            }
+            Shape::Line(line) => near_line(line),
+            Shape::Polygon(rings) => {
+                // even-odd rule, matching how polygons are filled
+                let inside = rings.iter().filter(|r| ring_contains(r, p)).count() % 2 == 1;
+                inside || rings.iter().any(|r| near_line(r))
+            }
+        }
+    }
+}
+
+// ray casting point in ring test
+fn ring_contains(ring: &[Px], p: &Px) -> bool {
+    let (x, y) = (p.x as f64, p.y as f64);
+    let mut inside = false;
+    let mut j = ring.len().wrapping_sub(1);
+    for (i, a) in ring.iter().enumerate() {
+        let b = &ring[j];
+        let (ax, ay, bx, by) = (a.x as f64, a.y as f64, b.x as f64, b.y as f64);
+        if (ay > y) != (by > y) && x < (bx - ax) * (y - ay) / (by - ay) + ax {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+impl Geometry {
+    /// Projects geometry to shapes with global pixel coordinates on zoom level `z`
+    pub fn project(&self, z: usize) -> Vec<Shape> {
+        let line = |ll: &[LonLat]| ll.iter().map(|p| p.px(z)).collect::<Vec<Px>>();
+        let polygon = |rings: &[Vec<LonLat>]| rings.iter().map(|r| line(r)).collect::<Vec<_>>();
+
+        match self {
+            Geometry::Point { coordinates } => vec![Shape::Point(coordinates.px(z))],
+            Geometry::MultiPoint { coordinates } => {
+                coordinates.iter().map(|p| Shape::Point(p.px(z))).collect()
+            }
+            Geometry::LineString { coordinates } => vec![Shape::Line(line(coordinates))],
+            Geometry::MultiLineString { coordinates } => {
+                coordinates.iter().map(|l| Shape::Line(line(l))).collect()
+            }
+            Geometry::Polygon { coordinates } => vec![Shape::Polygon(polygon(coordinates))],
+            Geometry::MultiPolygon { coordinates } => coordinates
+                .iter()
+                .map(|p| Shape::Polygon(polygon(p)))
+                .collect(),
+            Geometry::GeometryCollection { geometries } => {
+                geometries.iter().flat_map(|g| g.project(z)).collect()
+            }
+        }
+    }
+
+    /// Projects geometry to shapes with pixel offsets from viewport nw corner
+    pub fn shapes(&self, vw: &Viewport) -> Vec<Shape> {
+        let offset = vw.pixels().neg();
+        self.project(vw.z)
+            .iter()
+            .map(|s| s.translate(&offset))
+            .collect()
+    }
+}
+
+/// GeoJSON feature
+#[derive(Debug, PartialEq, Clone, Deserialize)]
+pub struct Feature {
+    #[serde(default)]
+    pub id: Option<Value>,
+    pub geometry: Option<Geometry>,
+    #[serde(default)]
+    pub properties: Option<Map<String, Value>>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum GeoJson {
+    FeatureCollection { features: Vec<Feature> },
+    Feature(Feature),
+}
+
+/// Drawing backend of vector layer or markers
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Renderer {
+    /// Svg elements, or html elements for markers, suitable for small layers
+    Svg,
+    /// Canvas 2D, suitable for large layers
+    Canvas,
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Renderer::Svg
+    }
+}
+
+/// Vector overlay layer from GeoJSON data
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct GeoJsonLayer {
+    /// Features to draw, shared between layer copies
+    pub features: Rc<Vec<Feature>>,
+    /// Style applied to all features
+    pub style: Style,
+    /// Data driven style overriding `style` per feature
+    pub style_spec: Option<Rc<StyleSpec>>,
+    /// Simplification of projected lines and polygons
+    pub simplify: Simplify,
+    pub renderer: Renderer,
+    /// Credit shown in attribution control, inserted as raw html
+    pub attribution: Option<String>,
+    /// Controls whether layer is visible on map
+    pub visible: bool,
+}
+
+impl GeoJsonLayer {
+    /// Creates layer from GeoJSON FeatureCollection or Feature
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let features = match serde_json::from_str(json)? {
+            GeoJson::FeatureCollection { features } => features,
+            GeoJson::Feature(feature) => vec![feature],
+        };
+        Ok(Self {
+            features: Rc::new(features),
+            style: Style::default(),
+            style_spec: None,
+            simplify: Simplify::default(),
+            renderer: Renderer::default(),
+            attribution: None,
+            visible: true,
+        })
+    }
+
+    /// Returns style of feature at index on zoom level `z`
+    pub fn feature_style(&self, feature: usize, z: usize) -> Cow<'_, Style> {
+        match &self.style_spec {
+            Some(spec) => {
+                let properties = self
+                    .features
+                    .get(feature)
+                    .and_then(|f| f.properties.as_ref());
+                Cow::Owned(spec.resolve(&self.style, z as f64, properties))
+            }
+            None => Cow::Borrowed(&self.style),
+        }
+    }
+
+    /// Returns index of topmost feature at global pixel point, given shapes of features on zoom level `z`
+    pub fn hit(&self, shapes: &[Vec<Shape>], z: usize, p: &Px, tolerance: f64) -> Option<usize> {
+        (0..shapes.len()).rev().find(|&i| {
+            let style = self.feature_style(i, z);
+            shapes[i].iter().any(|s| {
+                let radius = match s {
+                    Shape::Point(_) => style.radius + style.stroke_width / 2.0,
+                    _ => style.stroke_width / 2.0,
+                };
+                s.hit(p, radius, tolerance)
+            })
+        })
+    }
+
+    /// Clips shapes to outer viewport and moves them relative to viewport nw corner,
+    /// shapes are paired with feature index
+    pub fn view_shapes<'a>(
+        &self,
+        shapes: &'a [Vec<Shape>],
+        vw: &Viewport,
+        vw_outer: &Viewport,
+    ) -> impl Iterator<Item = (usize, Shape)> + 'a {
+        let (nw, se) = vw_outer.pixel_bounds();
+        let outer = Bounds::new(nw, se);
+        // leave room for strokes and points, data driven styles differ per feature
+        let pad = |style: &Style| outer.pad((style.stroke_width + style.radius).ceil() as i64);
+        let bounds: Vec<Bounds> = match self.style_spec {
+            Some(_) => (0..shapes.len())
+                .map(|i| pad(&self.feature_style(i, vw.z)))
+                .collect(),
+            None => vec![pad(&self.style)],
+        };
+        let offset = vw.pixels().neg();
+        shapes
+            .iter()
+            .enumerate()
+            .flat_map(|(i, feature)| feature.iter().map(move |s| (i, s)))
+            .flat_map(move |(i, s)| {
+                let b = &bounds[i.min(bounds.len() - 1)];
+                s.clip(b).into_iter().map(move |s| (i, s))
+            })
+            .map(move |(i, s)| (i, s.translate(&offset)))
+    }
+}
+
+/// Projected and simplified shapes of layer features, cached per zoom level
+#[derive(Default)]
+pub struct ShapeCache {
+    // source of cached shapes
+    features: Rc<Vec<Feature>>,
+    simplify: Simplify,
+    // shapes by zoom level, indexed by feature
+    zooms: HashMap<usize, Rc<Vec<Vec<Shape>>>>,
+}
+
+impl ShapeCache {
+    /// Returns feature shapes on zoom level `z` in global pixel coordinates
+    pub fn shapes(&mut self, layer: &GeoJsonLayer, z: usize) -> Rc<Vec<Vec<Shape>>> {
+        // invalidate if layer data or simplification changed
+        if !Rc::ptr_eq(&self.features, &layer.features) || self.simplify != layer.simplify {
+            self.features = layer.features.clone();
+            self.simplify = layer.simplify;
+            self.zooms.clear();
+        }
+        let simplify = self.simplify;
+        self.zooms
+            .entry(z)
+            .or_insert_with(|| {
+                Rc::new(
+                    layer
+                        .features
+                        .iter()
+                        .map(|f| match f.geometry {
+                            Some(ref g) => g
+                                .project(z)
+                                .iter()
+                                .filter_map(|s| s.simplify(&simplify))
+                                .collect(),
+                            None => vec![],
+                        })
+                        .collect(),
+                )
+            })
+            .clone()
+    }
+}
+
+// positions are deserialized from [lon, lat] arrays, ignoring altitude
+impl<'de> Deserialize<'de> for LonLat {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct PositionVisitor;
+
+        impl<'de> Visitor<'de> for PositionVisitor {
+            type Value = LonLat;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("position array [lon, lat]")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<LonLat, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let lon = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                let lat = seq
+                    .next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                // skip altitude and other extra values
+                while seq.next_element::<f64>()?.is_some() {}
+                Ok(LonLat { lon, lat })
+            }
+        }
+
+        deserializer.deserialize_seq(PositionVisitor)
+    }
+}
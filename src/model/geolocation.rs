@@ -0,0 +1,59 @@
+use super::LonLat;
+use failure::Fail;
+use std::fmt;
+
+/// User position from geolocation
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Location {
+    pub position: LonLat,
+    /// Accuracy radius in meters
+    pub accuracy: f64,
+}
+
+impl Location {
+    /// Returns accuracy radius in pixels on zoom level `z`
+    pub fn accuracy_px(&self, z: usize) -> f64 {
+        self.accuracy / self.position.meters_per_pixel(z)
+    }
+}
+
+/// Geolocation failure, codes follow the browser `GeolocationPositionError`
+#[derive(Debug, PartialEq, Clone)]
+pub enum GeolocationError {
+    PermissionDenied,
+    Unavailable,
+    Timeout,
+    /// Geolocation is not supported by browser
+    Unsupported,
+}
+
+impl GeolocationError {
+    /// Returns error of browser error code
+    pub fn from_code(code: u32) -> Self {
+        match code {
+            1 => GeolocationError::PermissionDenied,
+            3 => GeolocationError::Timeout,
+            _ => GeolocationError::Unavailable,
+        }
+    }
+    /// Returns true if retrying can not succeed without user action
+    pub fn is_permanent(&self) -> bool {
+        match self {
+            GeolocationError::PermissionDenied | GeolocationError::Unsupported => true,
+            GeolocationError::Unavailable | GeolocationError::Timeout => false,
+        }
+    }
+}
+
+impl fmt::Display for GeolocationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GeolocationError::PermissionDenied => write!(f, "geolocation permission denied"),
+            GeolocationError::Unavailable => write!(f, "position unavailable"),
+            GeolocationError::Timeout => write!(f, "geolocation timed out"),
+            GeolocationError::Unsupported => write!(f, "geolocation not supported"),
+        }
+    }
+}
+
+impl Fail for GeolocationError {}
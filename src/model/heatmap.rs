@@ -0,0 +1,183 @@
+use super::{LonLat, Px, Viewport};
+use std::rc::Rc;
+
+/// Heatmap point radius
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Radius {
+    Pixels(f64),
+    /// Ground distance, scaled by zoom level
+    Meters(f64),
+}
+
+/// Weighted point of heatmap
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct HeatPoint {
+    pub position: LonLat,
+    pub weight: f64,
+}
+
+/// Kernel density heatmap layer
+#[derive(Debug, PartialEq, Clone)]
+pub struct HeatmapLayer {
+    /// Points to draw, shared between layer copies
+    pub points: Rc<Vec<HeatPoint>>,
+    /// Radius of point influence
+    pub radius: Radius,
+    /// Faded share of radius, from 0 (hard edge) to 1
+    pub blur: f64,
+    /// Intensity drawn with the last gradient color
+    pub max: f64,
+    /// Color stops from 0 to 1 as rgb
+    pub gradient: Vec<(f64, [u8; 3])>,
+    pub opacity: f64,
+    /// Credit shown in attribution control, inserted as raw html
+    pub attribution: Option<String>,
+    /// Controls whether layer is visible on map
+    pub visible: bool,
+}
+
+impl Default for HeatmapLayer {
+    fn default() -> Self {
+        Self {
+            points: Rc::new(vec![]),
+            radius: Radius::Pixels(25.0),
+            blur: 0.6,
+            max: 1.0,
+            gradient: vec![
+                (0.4, [0, 0, 255]),
+                (0.6, [0, 255, 255]),
+                (0.7, [0, 255, 0]),
+                (0.8, [255, 255, 0]),
+                (1.0, [255, 0, 0]),
+            ],
+            opacity: 0.8,
+            attribution: None,
+            visible: true,
+        }
+    }
+}
+
+impl HeatmapLayer {
+    /// Creates layer from weighted points
+    pub fn new(points: Vec<HeatPoint>) -> Self {
+        Self {
+            points: Rc::new(points),
+            ..Default::default()
+        }
+    }
+
+    /// Returns point radius in pixels on viewport
+    pub fn radius_px(&self, vw: &Viewport) -> f64 {
+        match self.radius {
+            Radius::Pixels(r) => r,
+            Radius::Meters(m) => m / vw.center().meters_per_pixel(vw.z),
+        }
+    }
+
+    /// Returns width and height of heatmap image on viewport,
+    /// `scale` device pixels per css pixel
+    pub fn image_size(vw: &Viewport, scale: f64) -> (usize, usize) {
+        let size = vw.size();
+        let px = |v: i64| (v.max(0) as f64 * scale).round() as usize;
+        (px(size.x), px(size.y))
+    }
+
+    /// Returns region drawn for viewport, extended by half of its size on every side,
+    /// panning inside it moves the drawn image instead of summing intensities again
+    pub fn region(vw: &Viewport) -> Viewport {
+        let (nw, se) = vw.pixel_bounds();
+        let size = vw.size();
+        let margin: Px = (size.x / 2, size.y / 2).into();
+        Viewport::from_pixel_bounds(nw.translate(&margin.neg()), se.translate(&margin), vw.z)
+    }
+
+    /// Returns true if image drawn on `region` covers whole viewport
+    pub fn covers(region: &Viewport, vw: &Viewport) -> bool {
+        let ((rnw, rse), (nw, se)) = (region.pixel_bounds(), vw.pixel_bounds());
+        region.z == vw.z && rnw.x <= nw.x && rnw.y <= nw.y && rse.x >= se.x && rse.y >= se.y
+    }
+
+    /// Returns point intensities summed over viewport device pixels, row by row,
+    /// `scale` device pixels per css pixel
+    pub fn intensity(&self, vw: &Viewport, scale: f64) -> Vec<f64> {
+        let scale = if scale > 0.0 { scale } else { 1.0 };
+        let (w, h) = Self::image_size(vw, scale);
+        let mut grid = vec![0.0; w * h];
+
+        // kernel stamp, full inside inner radius and fading to zero at radius
+        let radius = (self.radius_px(vw) * scale).max(1.0);
+        let r = radius.ceil() as i64;
+        let inner = radius * (1.0 - self.blur.max(0.0).min(1.0));
+        let side = (2 * r + 1) as usize;
+        let mut kernel = vec![0.0; side * side];
+        for (dy, dx) in iproduct!(-r..=r, -r..=r) {
+            let d = ((dx * dx + dy * dy) as f64).sqrt();
+            let k = if d <= inner {
+                1.0
+            } else if d < radius {
+                // smoothstep falloff
+                let t = (radius - d) / (radius - inner);
+                t * t * (3.0 - 2.0 * t)
+            } else {
+                0.0
+            };
+            kernel[((dy + r) as usize) * side + (dx + r) as usize] = k;
+        }
+
+        for p in self.points.iter() {
+            let c = vw.point_offset(&p.position);
+            let c: Px = ((c.x as f64 * scale).round(), (c.y as f64 * scale).round()).into();
+            // skip points outside viewport and their radius
+            if c.x < -r || c.y < -r || c.x > w as i64 + r || c.y > h as i64 + r {
+                continue;
+            }
+            for (dy, dx) in iproduct!(-r..=r, -r..=r) {
+                let (x, y) = (c.x + dx, c.y + dy);
+                if x >= 0 && y >= 0 && (x as usize) < w && (y as usize) < h {
+                    grid[y as usize * w + x as usize] +=
+                        p.weight * kernel[((dy + r) as usize) * side + (dx + r) as usize];
+                }
+            }
+        }
+        grid
+    }
+
+    /// Returns RGBA image of heatmap on viewport, sized by `image_size`
+    pub fn image(&self, vw: &Viewport, scale: f64) -> Vec<u8> {
+        let palette = self.palette();
+        let max = if self.max > 0.0 { self.max } else { 1.0 };
+        let grid = self.intensity(vw, scale);
+        let mut image = Vec::with_capacity(grid.len() * 4);
+        for v in grid {
+            // negative weights and nan are drawn as zero intensity
+            let v = (v / max).max(0.0).min(1.0);
+            let [r, g, b] = palette[(v * 255.0).round() as usize];
+            // transparency grows with intensity
+            let a = (v * self.opacity * 255.0).round().max(0.0).min(255.0) as u8;
+            image.extend_from_slice(&[r, g, b, a]);
+        }
+        image
+    }
+
+    // interpolates gradient to 256 colors
+    fn palette(&self) -> Vec<[u8; 3]> {
+        (0..256)
+            .map(|i| {
+                let v = f64::from(i) / 255.0;
+                let next = self.gradient.iter().position(|(stop, _)| *stop >= v);
+                match next {
+                    Some(0) => self.gradient[0].1,
+                    Some(n) => {
+                        let ((s0, c0), (s1, c1)) = (self.gradient[n - 1], self.gradient[n]);
+                        let t = (v - s0) / (s1 - s0);
+                        let mix = |a: u8, b: u8| {
+                            (f64::from(a) + (f64::from(b) - f64::from(a)) * t).round() as u8
+                        };
+                        [mix(c0[0], c1[0]), mix(c0[1], c1[1]), mix(c0[2], c1[2])]
+                    }
+                    None => self.gradient.last().map(|g| g.1).unwrap_or([0, 0, 0]),
+                }
+            })
+            .collect()
+    }
+}
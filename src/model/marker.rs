@@ -0,0 +1,93 @@
+use super::{Cluster, LonLat, Px, Viewport};
+
+/// Radius of default marker circle in pixels, border included
+pub const MARKER_RADIUS: f64 = 9.0;
+/// Radius of cluster circle in pixels
+pub const CLUSTER_RADIUS: f64 = 15.0;
+
+/// Marker placed on map coordinates
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Marker {
+    /// Marker position
+    pub position: LonLat,
+    /// Icon image url, default icon is drawn if none is given
+    pub icon: Option<String>,
+    /// Html content, drawn instead of icon, inserted unsanitized
+    pub html: Option<String>,
+    /// Pixel offset from top-left corner of the marker to its position point
+    pub anchor: Px,
+    /// Icon size in pixels, natural image size if none is given.
+    /// Canvas drawn icons without size are hit as default markers.
+    pub size: Option<Px>,
+    /// Additional css class
+    pub class: String,
+    /// Html content of popup opened by clicking the marker
+    pub popup: Option<String>,
+}
+
+impl Marker {
+    /// Creates marker with default icon on given position
+    pub fn new(position: LonLat) -> Self {
+        Self {
+            position,
+            ..Default::default()
+        }
+    }
+}
+
+/// Canvas drawn marker or cluster under pointer
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum MarkerHit {
+    /// Marker index
+    Marker(usize),
+    /// Cluster index
+    Cluster(usize),
+}
+
+impl Marker {
+    /// Checks if pixel offset `p` in viewport is on marker,
+    /// assuming marker is drawn as icon of its size or default circle
+    pub fn hit(&self, vw: &Viewport, p: &Px) -> bool {
+        let origin = vw.point_offset(&self.position).distance(&self.anchor);
+        match (&self.icon, self.size) {
+            (Some(_), Some(size)) => {
+                let d = p.distance(&origin);
+                d.x >= 0 && d.y >= 0 && d.x <= size.x && d.y <= size.y
+            }
+            _ => within(&origin, p, MARKER_RADIUS),
+        }
+    }
+}
+
+// checks if p is inside circle
+fn within(center: &Px, p: &Px, radius: f64) -> bool {
+    let d = p.distance(center);
+    ((d.x * d.x + d.y * d.y) as f64).sqrt() <= radius
+}
+
+/// Finds topmost canvas drawn marker or cluster at pixel offset `p` in viewport.
+/// Markers with html content are drawn as elements and never hit.
+pub fn hit_markers(
+    markers: &[Marker],
+    clusters: Option<&[Cluster]>,
+    vw: &Viewport,
+    p: &Px,
+) -> Option<MarkerHit> {
+    let marker = |i: usize| match markers.get(i) {
+        Some(m) if m.html.is_none() && m.hit(vw, p) => Some(MarkerHit::Marker(i)),
+        _ => None,
+    };
+    match clusters {
+        // single point clusters are drawn as their marker
+        Some(clusters) => clusters.iter().enumerate().rev().find_map(|(i, c)| {
+            if c.count() == 1 {
+                marker(c.members[0])
+            } else if within(&vw.point_offset(&c.position), p, CLUSTER_RADIUS) {
+                Some(MarkerHit::Cluster(i))
+            } else {
+                None
+            }
+        }),
+        None => (0..markers.len()).rev().find_map(marker),
+    }
+}
@@ -0,0 +1,137 @@
+use super::position::MEAN_EARTH_RADIUS;
+use super::LonLat;
+
+/// Measured quantity
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum MeasureKind {
+    /// Path length along vertices
+    Distance,
+    /// Area of polygon closed between last and first vertex
+    Area,
+}
+
+/// Measurement drawn on map
+#[derive(Debug, PartialEq, Clone)]
+pub struct Measurement {
+    pub kind: MeasureKind,
+    pub vertices: Vec<LonLat>,
+    /// Finished measurements take no more vertices
+    pub finished: bool,
+}
+
+impl Measurement {
+    pub fn new(kind: MeasureKind) -> Self {
+        Self {
+            kind,
+            vertices: vec![],
+            finished: false,
+        }
+    }
+
+    /// Adds vertex, repeated vertices of a double click are ignored
+    pub fn add(&mut self, ll: LonLat) {
+        if !self.finished && self.vertices.last() != Some(&ll) {
+            self.vertices.push(ll);
+        }
+    }
+
+    /// Adds vertex of map click, click after finishing starts a new measurement
+    pub fn click(&mut self, ll: LonLat) {
+        if self.finished {
+            *self = Measurement::new(self.kind);
+        }
+        self.add(ll);
+    }
+
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    /// Returns vertices followed by preview point while measuring
+    pub fn points(&self, preview: Option<&LonLat>) -> Vec<LonLat> {
+        let mut points = self.vertices.clone();
+        match preview {
+            Some(ll) if !self.finished && !points.is_empty() => points.push(*ll),
+            _ => (),
+        }
+        points
+    }
+
+    /// Returns segment lengths in meters, polygon closing segment included
+    pub fn segments(&self, preview: Option<&LonLat>) -> Vec<f64> {
+        let mut points = self.points(preview);
+        if self.kind == MeasureKind::Area && points.len() > 2 {
+            points.push(points[0]);
+        }
+        points
+            .windows(2)
+            .map(|w| geodesic_distance(&w[0], &w[1]))
+            .collect()
+    }
+
+    /// Returns path length or polygon perimeter in meters
+    pub fn distance(&self, preview: Option<&LonLat>) -> f64 {
+        self.segments(preview).iter().sum()
+    }
+
+    /// Returns polygon area in square meters, zero for distance measurements
+    pub fn area(&self, preview: Option<&LonLat>) -> f64 {
+        match self.kind {
+            MeasureKind::Area => geodesic_area(&self.points(preview)),
+            MeasureKind::Distance => 0.0,
+        }
+    }
+}
+
+/// Returns ellipsoidal distance in meters, great-circle distance if Vincenty does not converge
+pub fn geodesic_distance(a: &LonLat, b: &LonLat) -> f64 {
+    a.vincenty_distance(b).unwrap_or_else(|| a.distance(b))
+}
+
+/// Returns area of polygon ring in square meters, ring may be open or closed
+///
+/// Area is approximated on a sphere of mean earth radius, unlike distances measured on the
+/// WGS84 ellipsoid. Edges take the shorter way around, so rings may cross the antimeridian.
+/// https://trs.jpl.nasa.gov/handle/2014/41271
+pub fn geodesic_area(ring: &[LonLat]) -> f64 {
+    if ring.len() < 3 {
+        return 0.0;
+    }
+    let n = ring.len();
+    let sum: f64 = (0..n)
+        .map(|i| {
+            let (a, b) = (&ring[i], &ring[(i + 1) % n]);
+            // longitude difference in -180..180
+            let mut d = b.lon - a.lon;
+            if d > 180.0 {
+                d -= 360.0;
+            } else if d < -180.0 {
+                d += 360.0;
+            }
+            d.to_radians() * (2.0 + a.lat.to_radians().sin() + b.lat.to_radians().sin())
+        })
+        .sum();
+    (sum * MEAN_EARTH_RADIUS * MEAN_EARTH_RADIUS / 2.0).abs()
+}
+
+/// Formats distance in meters or kilometers
+pub fn format_distance(meters: f64) -> String {
+    if meters < 1000.0 {
+        format!("{:.0} m", meters)
+    } else if meters < 100_000.0 {
+        format!("{:.2} km", meters / 1000.0)
+    } else {
+        format!("{:.0} km", meters / 1000.0)
+    }
+}
+
+/// Formats area in square meters, hectares or square kilometers
+pub fn format_area(sq_meters: f64) -> String {
+    if sq_meters < 10_000.0 {
+        format!("{:.0} m²", sq_meters)
+    } else if sq_meters < 1_000_000.0 {
+        format!("{:.2} ha", sq_meters / 10_000.0)
+    } else {
+        format!("{:.2} km²", sq_meters / 1_000_000.0)
+    }
+}
@@ -1,15 +1,47 @@
+pub mod attribution;
+pub mod clip;
+pub mod cluster;
+pub mod expr;
+pub mod geojson;
+pub mod geolocation;
+pub mod heatmap;
+pub mod marker;
+pub mod measure;
+pub mod mvt;
+pub mod popup;
 pub mod position;
+pub mod scale;
+pub mod simplify;
+pub mod style;
+pub mod template;
 pub mod tile;
 pub mod viewport;
 
-pub use position::{LonLat, Px};
-pub use tile::{Tile, TileLayer};
+pub use attribution::attributions;
+pub use clip::Bounds;
+pub use cluster::{Cluster, ClusterIndex, Clustering};
+pub use expr::{Expr, Kind};
+pub use geojson::{Feature, GeoJsonLayer, Geometry, Renderer, Shape, ShapeCache};
+pub use geolocation::{GeolocationError, Location};
+pub use heatmap::{HeatPoint, HeatmapLayer, Radius};
+pub use marker::{hit_markers, Marker, MarkerHit};
+pub use measure::{MeasureKind, Measurement};
+pub use mvt::{GeomType, MvtError, MvtFeature, MvtLayer, StyleRule, VectorTile, VectorTileLayer};
+pub use popup::Popup;
+pub use position::{CoordFormat, LonLat, Px};
+pub use scale::{ScaleBar, Units};
+pub use simplify::Simplify;
+pub use style::{Style, StyleError, StyleSpec};
+pub use template::{TemplateError, UrlTemplate};
+pub use tile::{Crs, Retina, Scheme, Tile, TileLayer, Wms, Wmts};
 pub use viewport::Viewport;
 
 #[cfg(test)]
 mod tests {
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::*;
+    use position::{EARTH_RADIUS, MEAN_EARTH_RADIUS};
+    use std::f64::consts::PI;
 
     #[test]
     fn test_tiles_in_bb_1() {
@@ -115,6 +147,1010 @@ mod tests {
         assert!((lat - 67.37369797436554) < 1e-10);
     }
 
+    #[test]
+    fn test_overzoom_1() {
+        let mut layer = TileLayer::new("").unwrap();
+        layer.max_native_zoom = 16;
+
+        assert_eq!(layer.source_zoom(12, false), 12);
+        assert_eq!(layer.source_zoom(20, false), 16);
+
+        // native tile scaled to zoom 20 covers the same pixels as its 16 descendants
+        let tile = Tile::from_lonlat(29.76, 62.6, 20);
+        let ancestor = Tile::from_lonlat(29.76, 62.6, 16);
+        let offset = tile.pixels_at(20).distance(&ancestor.pixels_at(20));
+
+        assert_eq!(ancestor.size_at(20), 4096);
+        assert_eq!(offset.x, (tile.x as i64 % 16) * 256);
+        assert_eq!(offset.y, (tile.y as i64 % 16) * 256);
+    }
+
+    #[test]
+    fn test_tile_size_1() {
+        let mut layer = TileLayer::new("https://example.org/{z}/{x}/{y}{r}.png").unwrap();
+        layer.tile_size = 512;
+
+        // 512px tiles are fetched from one zoom level lower
+        let z = layer.source_zoom(10, false);
+        assert_eq!(z, 9);
+        assert_eq!(Tile { x: 3, y: 5, z }.size_at(10), 512);
+        assert_eq!(Tile { x: 3, y: 5, z }.pixels_at(10), (1536, 2560).into());
+
+        // retina zoom on 512px tiles draws them at half size
+        layer.retina = Retina::Zoom;
+        assert_eq!(layer.source_zoom(10, true), 10);
+        assert_eq!(layer.source_zoom(10, false), 9);
+    }
+
+    #[test]
+    fn test_tile_size_2() {
+        let mut layer = TileLayer::new("https://example.org/{z}/{x}/{y}{r}.png").unwrap();
+        let tile = Tile { x: 1, y: 2, z: 3 };
+
+        layer.retina = Retina::Zoom;
+        assert_eq!(layer.source_zoom(3, true), 4);
+        assert_eq!(Tile { x: 2, y: 4, z: 4 }.size_at(3), 128);
+        assert_eq!(layer.tile_url(&tile, true), "https://example.org/3/1/2.png");
+
+        layer.retina = Retina::Suffix;
+        assert_eq!(layer.source_zoom(3, true), 3);
+        assert_eq!(
+            layer.tile_url(&tile, true),
+            "https://example.org/3/1/2@2x.png"
+        );
+        assert_eq!(
+            layer.tile_url(&tile, false),
+            "https://example.org/3/1/2.png"
+        );
+    }
+
+    #[test]
+    fn test_scheme_1() {
+        let tile = Tile { x: 3, y: 5, z: 3 };
+        assert_eq!(tile.tms_y(), 2);
+        assert_eq!(tile.quadkey(), "213");
+        assert_eq!(Tile { x: 0, y: 0, z: 0 }.quadkey(), "");
+
+        let mut layer = TileLayer::new("https://example.org/{z}/{x}/{y}{r}.png").unwrap();
+        layer.scheme = Scheme::Tms;
+        assert_eq!(
+            layer.tile_url(&tile, false),
+            "https://example.org/3/3/2.png"
+        );
+
+        let layer = TileLayer::new("https://example.org/{q}.png").unwrap();
+        assert_eq!(layer.tile_url(&tile, false), "https://example.org/213.png");
+    }
+
+    #[test]
+    fn test_scheme_2() {
+        let tile = Tile { x: 3, y: 5, z: 3 };
+        let mut layer = TileLayer::new("https://example.org/gwc/service/wmts").unwrap();
+        layer.scheme = Scheme::Wmts(Wmts {
+            layer: "topp:states".into(),
+            style: "".into(),
+            format: "image/png".into(),
+            tile_matrix_set: "EPSG:900913".into(),
+            tile_matrix_prefix: "EPSG:900913:".into(),
+        });
+
+        assert_eq!(
+            layer.tile_url(&tile, false),
+            "https://example.org/gwc/service/wmts?SERVICE=WMTS&REQUEST=GetTile&VERSION=1.0.0\
+             &LAYER=topp:states&STYLE=&FORMAT=image/png&TILEMATRIXSET=EPSG:900913\
+             &TILEMATRIX=EPSG:900913:3&TILEROW=5&TILECOL=3"
+        );
+
+        // names are percent-encoded
+        layer.scheme = Scheme::Wmts(Wmts {
+            layer: "roads & rails".into(),
+            style: "a+b".into(),
+            format: "image/jpeg".into(),
+            tile_matrix_set: "grid=1".into(),
+            tile_matrix_prefix: "".into(),
+        });
+        assert!(layer.tile_url(&tile, false).contains(
+            "&LAYER=roads%20%26%20rails&STYLE=a%2Bb&FORMAT=image/jpeg&TILEMATRIXSET=grid%3D1&"
+        ));
+    }
+
+    #[test]
+    fn test_scheme_3() {
+        let mut layer = TileLayer::new("https://example.org/geoserver/wms").unwrap();
+        layer.scheme = Scheme::Wms(Wms {
+            layers: "topp:states".into(),
+            ..Wms::default()
+        });
+
+        assert_eq!(
+            layer.tile_url(&Tile { x: 0, y: 0, z: 1 }, false),
+            "https://example.org/geoserver/wms?SERVICE=WMS&REQUEST=GetMap&VERSION=1.3.0\
+             &LAYERS=topp:states&STYLES=&FORMAT=image/png&TRANSPARENT=TRUE\
+             &CRS=EPSG:3857&BBOX=-20037508.342789244,0,0,20037508.342789244\
+             &WIDTH=256&HEIGHT=256"
+        );
+    }
+
+    #[test]
+    fn test_scheme_4() {
+        let mut layer = TileLayer::new("https://example.org/geoserver/wms?key=foobar").unwrap();
+        let mut wms = Wms {
+            layers: "topp:states".into(),
+            crs: Crs::Epsg4326,
+            transparent: false,
+            ..Wms::default()
+        };
+        layer.scheme = Scheme::Wms(wms.clone());
+        layer.retina = Retina::Suffix;
+
+        let url = layer.tile_url(&Tile { x: 1, y: 0, z: 1 }, true);
+        assert!(url.starts_with("https://example.org/geoserver/wms?key=foobar&SERVICE=WMS"));
+        assert!(url.contains("&TRANSPARENT=FALSE&CRS=EPSG:4326&BBOX=0,0,85.0511287798066,180&"));
+        assert!(url.ends_with("&WIDTH=512&HEIGHT=512"));
+
+        wms.version = "1.1.1".into();
+        layer.scheme = Scheme::Wms(wms.clone());
+        let url = layer.tile_url(&Tile { x: 1, y: 0, z: 1 }, false);
+        assert!(url.contains("&SRS=EPSG:4326&BBOX=0,0,180,85.0511287798066&"));
+
+        // later versions keep 1.3.0 axis order, names are percent-encoded
+        wms.version = "1.3".into();
+        wms.layers = "roads & rails,water".into();
+        wms.styles = "a+b,".into();
+        layer.scheme = Scheme::Wms(wms);
+        let url = layer.tile_url(&Tile { x: 1, y: 0, z: 1 }, false);
+        assert!(url.contains("&LAYERS=roads%20%26%20rails,water&STYLES=a%2Bb,&"));
+        assert!(url.contains("&CRS=EPSG:4326&BBOX=0,0,85.0511287798066,180&"));
+    }
+
+    #[test]
+    fn test_template_1() {
+        let layer = TileLayer::with_values(
+            "https://{s}.tile.example.org/{z}/{x}/{-y}.png?key={apikey}",
+            &[("apikey", "foobar")],
+        )
+        .unwrap();
+
+        assert_eq!(
+            layer.tile_url(&Tile { x: 3, y: 5, z: 3 }, false),
+            "https://c.tile.example.org/3/3/2.png?key=foobar"
+        );
+        // subdomain is rotated deterministically between neighbouring tiles
+        assert_eq!(
+            layer.tile_url(&Tile { x: 4, y: 5, z: 3 }, false),
+            "https://a.tile.example.org/3/4/2.png?key=foobar"
+        );
+        assert_eq!(
+            layer.tile_url(&Tile { x: 3, y: 5, z: 3 }, true),
+            layer.tile_url(&Tile { x: 3, y: 5, z: 3 }, false)
+        );
+    }
+
+    #[test]
+    fn test_template_2() {
+        let template = "https://tile.example.org/{z}/{x}/{y}.png?key={apikey}";
+        assert_eq!(
+            UrlTemplate::parse(template, &[]),
+            Err(TemplateError::Unknown("apikey".into(), template.into()))
+        );
+        assert_eq!(
+            UrlTemplate::parse("https://example.org/{z}/{x", &[]),
+            Err(TemplateError::Unclosed(
+                24,
+                "https://example.org/{z}/{x".into()
+            ))
+        );
+        assert_eq!(
+            UrlTemplate::parse("https://example.org/z}", &[]),
+            Err(TemplateError::UnexpectedClose(
+                21,
+                "https://example.org/z}".into()
+            ))
+        );
+        assert_eq!(
+            UrlTemplate::parse("https://example.org/{}", &[]),
+            Err(TemplateError::Empty(20, "https://example.org/{}".into()))
+        );
+        assert_eq!(
+            TemplateError::Unknown("apikey".into(), template.into()).to_string(),
+            "unknown placeholder '{apikey}' in url template \
+             'https://tile.example.org/{z}/{x}/{y}.png?key={apikey}', no value given for it"
+        );
+    }
+
+    #[test]
+    fn test_template_3() {
+        let tile = Tile { x: 3, y: 5, z: 3 };
+        let mut layer = TileLayer::new("https://example.org/{z}/{x}/{-y}/{y}.png").unwrap();
+        assert_eq!(
+            layer.tile_url(&tile, false),
+            "https://example.org/3/3/2/5.png"
+        );
+        // '{-y}' always means the tms row, also with tms scheme
+        layer.scheme = Scheme::Tms;
+        assert_eq!(
+            layer.tile_url(&tile, false),
+            "https://example.org/3/3/2/2.png"
+        );
+    }
+
+    #[test]
+    fn test_geodesy_distance() {
+        let lax = LonLat::from((-118.4081, 33.9425));
+        let jfk = LonLat::from((-73.7789, 40.6398));
+        assert!((lax.distance(&jfk) - 3_974_210.93).abs() < 0.01);
+        assert_eq!(jfk.distance(&jfk), 0.0);
+
+        // flinders peak to buninyong, from vincenty's paper
+        let a = LonLat::from((144.424_867_888_9, -37.951_033_416_7));
+        let b = LonLat::from((143.926_495_527_8, -37.652_821_138_9));
+        assert!((a.vincenty_distance(&b).unwrap() - 54_972.271).abs() < 0.001);
+        assert_eq!(a.vincenty_distance(&a), Some(0.0));
+
+        // nearly antipodal points do not converge
+        let c = LonLat::from((0.0, 0.0));
+        let d = LonLat::from((179.7, 0.5));
+        assert_eq!(c.vincenty_distance(&d), None);
+    }
+
+    #[test]
+    fn test_geodesy_bearing() {
+        let origin = LonLat::from((0.0, 0.0));
+        assert!((origin.bearing(&(0.0, 10.0).into()) - 0.0).abs() < 1e-10);
+        assert!((origin.bearing(&(10.0, 0.0).into()) - 90.0).abs() < 1e-10);
+        assert!((origin.bearing(&(0.0, -10.0).into()) - 180.0).abs() < 1e-10);
+        assert!((origin.bearing(&(-10.0, 0.0).into()) - 270.0).abs() < 1e-10);
+
+        // travelling quarter of earth circumference east along equator
+        let quarter = PI / 2.0 * MEAN_EARTH_RADIUS;
+        let p = origin.destination(90.0, quarter);
+        assert!((p.lon - 90.0).abs() < 1e-10 && p.lat.abs() < 1e-10);
+        // crossing antimeridian wraps longitude
+        let p = LonLat::from((170.0, 0.0)).destination(90.0, quarter);
+        assert!((p.lon - -100.0).abs() < 1e-10);
+
+        // destination and bearing agree
+        let helsinki = LonLat::from((24.9384, 60.1699));
+        let joensuu = LonLat::from((29.7636, 62.6010));
+        let p = helsinki.destination(helsinki.bearing(&joensuu), helsinki.distance(&joensuu));
+        assert!(p.distance(&joensuu) < 1e-3);
+    }
+
+    #[test]
+    fn test_geodesy_interpolate() {
+        let a = LonLat::from((0.0, 0.0));
+        let b = LonLat::from((90.0, 0.0));
+        let m = a.midpoint(&b);
+        assert!((m.lon - 45.0).abs() < 1e-10 && m.lat.abs() < 1e-10);
+
+        let helsinki = LonLat::from((24.9384, 60.1699));
+        let joensuu = LonLat::from((29.7636, 62.6010));
+        assert!(helsinki.interpolate(&joensuu, 0.0).distance(&helsinki) < 1e-6);
+        assert!(helsinki.interpolate(&joensuu, 1.0).distance(&joensuu) < 1e-6);
+
+        // point a quarter along lies on the great circle at a quarter of the distance
+        let d = helsinki.distance(&joensuu);
+        let p = helsinki.interpolate(&joensuu, 0.25);
+        assert!((helsinki.distance(&p) - d * 0.25).abs() < 1e-3);
+        assert!((p.distance(&joensuu) - d * 0.75).abs() < 1e-3);
+        // great circle bulges north from the rhumb line midpoint
+        assert!(helsinki.midpoint(&joensuu).lat > (60.1699 + 62.6010) / 2.0);
+    }
+
+    #[test]
+    fn test_point_offset_1() {
+        let vw = Viewport::new(&(29.76, 62.6).into(), (800, 600), 12);
+        let offset = vw.point_offset(&vw.center());
+        assert!((offset.x - 400).abs() <= 1);
+        assert!((offset.y - 300).abs() <= 1);
+
+        // point on tile corner has same offset as the tile
+        let tile = vw.tiles().next().unwrap();
+        assert_eq!(vw.point_offset(&tile.nw().into()), vw.pixel_offset(&tile));
+    }
+
+    #[test]
+    fn test_popup_autopan() {
+        let container: Px = (800, 600).into();
+        let size: Px = (200, 100).into();
+        // fits already
+        assert_eq!(
+            Popup::autopan_offset((100, 100).into(), size, container, 10),
+            (0, 0).into()
+        );
+        // overflows top-left
+        assert_eq!(
+            Popup::autopan_offset((-50, 5).into(), size, container, 10),
+            (60, 5).into()
+        );
+        // overflows bottom-right
+        assert_eq!(
+            Popup::autopan_offset((700, 550).into(), size, container, 10),
+            (-110, -60).into()
+        );
+        // larger than container keeps top-left corner visible
+        assert_eq!(
+            Popup::autopan_offset((100, 100).into(), (1000, 100).into(), container, 10),
+            (-90, 0).into()
+        );
+    }
+
+    #[test]
+    fn test_geojson_1() {
+        let layer = GeoJsonLayer::from_json(
+            r#"{"type": "FeatureCollection", "features": [
+                {"type": "Feature", "id": 1, "properties": {"name": "a"},
+                 "geometry": {"type": "Point", "coordinates": [24.9, 60.2, 12.0]}},
+                {"type": "Feature", "properties": null,
+                 "geometry": {"type": "Polygon", "coordinates": [
+                    [[0, 0], [10, 0], [10, 10], [0, 0]],
+                    [[2, 2], [4, 2], [4, 4], [2, 2]]]}},
+                {"type": "Feature", "properties": {}, "geometry": null}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(layer.features.len(), 3);
+        assert_eq!(
+            layer.features[0].geometry,
+            Some(Geometry::Point {
+                coordinates: (24.9, 60.2).into()
+            })
+        );
+        match layer.features[1].geometry {
+            Some(Geometry::Polygon { ref coordinates }) => assert_eq!(coordinates.len(), 2),
+            _ => panic!("expected polygon"),
+        }
+        assert_eq!(layer.features[2].geometry, None);
+        // invalid positions
+        assert!(GeoJsonLayer::from_json(
+            r#"{"type": "Feature", "geometry": {"type": "Point", "coordinates": [1]}}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_geojson_shapes_1() {
+        let vw = Viewport::new(&(0.0, 0.0).into(), (512, 512), 1);
+        let g = Geometry::GeometryCollection {
+            geometries: vec![
+                Geometry::MultiPoint {
+                    coordinates: vec![(0.0, 0.0).into(), (-180.0, 0.0).into()],
+                },
+                Geometry::LineString {
+                    coordinates: vec![(0.0, 0.0).into(), (90.0, 0.0).into()],
+                },
+            ],
+        };
+        assert_eq!(
+            g.shapes(&vw),
+            vec![
+                Shape::Point((256, 256).into()),
+                Shape::Point((0, 256).into()),
+                Shape::Line(vec![(256, 256).into(), (384, 256).into()]),
+            ]
+        );
+        assert_eq!(vw.size(), (512, 512).into());
+    }
+
+    #[test]
+    fn test_simplify_1() {
+        let line: Vec<Px> = vec![
+            (0, 0).into(),
+            (1, 0).into(),
+            (1, 0).into(),
+            (2, 1).into(),
+            (3, 0).into(),
+            (10, 0).into(),
+            (10, 10).into(),
+        ];
+        assert_eq!(
+            Simplify::DouglasPeucker(1.0).apply(&line),
+            vec![(0, 0).into(), (10, 0).into(), (10, 10).into()]
+        );
+        assert_eq!(
+            Simplify::Visvalingam(2.0).apply(&line),
+            vec![(0, 0).into(), (10, 0).into(), (10, 10).into()]
+        );
+        // only duplicates are removed
+        assert_eq!(Simplify::Off.apply(&line).len(), 6);
+        // collinear point is removed
+        assert_eq!(Simplify::DouglasPeucker(0.5).apply(&line).len(), 5);
+    }
+
+    #[test]
+    fn test_clip_1() {
+        let bounds = Bounds::new((0, 0).into(), (10, 10).into());
+        // line leaving and entering bounds
+        let line: Vec<Px> = vec![
+            (-5, 5).into(),
+            (5, 5).into(),
+            (5, 20).into(),
+            (8, 20).into(),
+            (8, 5).into(),
+        ];
+        assert_eq!(
+            bounds.clip_line(&line),
+            vec![
+                vec![(0, 5).into(), (5, 5).into(), (5, 10).into()],
+                vec![(8, 10).into(), (8, 5).into()],
+            ]
+        );
+        // square overlapping top-left corner
+        let ring: Vec<Px> = vec![
+            (-5, -5).into(),
+            (5, -5).into(),
+            (5, 5).into(),
+            (-5, 5).into(),
+            (-5, -5).into(),
+        ];
+        assert_eq!(
+            bounds.clip_ring(&ring),
+            vec![(0, 0).into(), (5, 0).into(), (5, 5).into(), (0, 5).into()]
+        );
+        assert_eq!(
+            Shape::Polygon(vec![ring
+                .iter()
+                .map(|p| p.translate(&(-20, 0).into()))
+                .collect()])
+            .clip(&bounds),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_shape_cache_1() {
+        let mut layer = GeoJsonLayer::from_json(
+            r#"{"type": "Feature", "geometry": {"type": "LineString",
+                "coordinates": [[0, 0], [0.001, 0.001], [10, 0], [20, 0]]}}"#,
+        )
+        .unwrap();
+        let mut cache = ShapeCache::default();
+        let a = cache.shapes(&layer, 2);
+        // small wiggle and collinear point are dropped on low zoom
+        assert_eq!(
+            a[0],
+            vec![Shape::Line(vec![(512, 512).into(), (569, 512).into()])]
+        );
+        assert!(std::rc::Rc::ptr_eq(&a, &cache.shapes(&layer, 2)));
+        layer.simplify = Simplify::Off;
+        match cache.shapes(&layer, 2)[0][0] {
+            Shape::Line(ref l) => assert_eq!(l.len(), 3),
+            _ => panic!("expected line"),
+        }
+    }
+
+    #[test]
+    fn test_cluster_1() {
+        let points: Vec<LonLat> = vec![
+            (24.94, 60.17).into(),
+            (24.95, 60.17).into(),
+            (24.96, 60.18).into(),
+            (29.76, 62.60).into(),
+        ];
+        let index = ClusterIndex::new(&points, &Clustering::default());
+        // nearby points are clustered on low zoom
+        let c = index.clusters(5);
+        assert_eq!(c.len(), 2);
+        assert_eq!(c[0].members, vec![0, 1, 2]);
+        assert_eq!(c[0].nw, (24.94, 60.18).into());
+        assert_eq!(c[0].se, (24.96, 60.17).into());
+        assert!((c[0].position.lon - 24.95).abs() < 1e-9);
+        assert_eq!(c[1].count(), 1);
+        // single cluster on lowest zoom
+        assert_eq!(index.clusters(0).len(), 1);
+        // no clustering above max zoom
+        assert_eq!(index.clusters(17).len(), 4);
+        assert_eq!(index.clusters(22).len(), 4);
+        // zoom to fit first cluster
+        let z = Viewport::fit_zoom(&c[0].nw, &c[0].se, (256, 256), 22);
+        let d = c[0].se.px(z).distance(&c[0].nw.px(z));
+        assert!(d.x <= 256 && d.x > 128);
+    }
+
+    #[test]
+    fn test_marker_hit_1() {
+        let vw = Viewport::new(&(0.0, 0.0).into(), (200, 200), 2);
+        let center = vw.center();
+        let mut icon = Marker::new(center);
+        icon.icon = Some("pin.png".into());
+        icon.anchor = (10, 30).into();
+        icon.size = Some((20, 30).into());
+        let mut html = Marker::new(center);
+        html.html = Some("<b>x</b>".into());
+        let markers = vec![Marker::new(center), icon, html];
+
+        // html marker on top is drawn as element and never hit
+        assert_eq!(
+            hit_markers(&markers, None, &vw, &(100, 90).into()),
+            Some(MarkerHit::Marker(1))
+        );
+        assert_eq!(
+            hit_markers(&markers, None, &vw, &(100, 106).into()),
+            Some(MarkerHit::Marker(0))
+        );
+        assert_eq!(hit_markers(&markers, None, &vw, &(150, 150).into()), None);
+
+        let cluster = Cluster {
+            position: center,
+            members: vec![0, 1],
+            nw: center,
+            se: center,
+        };
+        let clusters = vec![cluster];
+        assert_eq!(
+            hit_markers(&markers, Some(&clusters), &vw, &(110, 100).into()),
+            Some(MarkerHit::Cluster(0))
+        );
+        assert_eq!(
+            hit_markers(&markers, Some(&clusters), &vw, &(100, 120).into()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_view_shapes_1() {
+        let mut layer = GeoJsonLayer::from_json(
+            r#"{"type": "Feature", "geometry": {"type": "MultiPoint",
+                "coordinates": [[0, 0], [-52.03125, 0], [-60, 0]]}}"#,
+        )
+        .unwrap();
+        layer.style.radius = 10.0;
+        layer.style.stroke_width = 0.0;
+        let vw = Viewport::new(&(45.0, 0.0).into(), (256, 256), 1);
+        let shapes = ShapeCache::default().shapes(&layer, vw.z);
+        // points near the viewport edge are kept for their radius
+        assert_eq!(
+            layer.view_shapes(&shapes, &vw, &vw).collect::<Vec<_>>(),
+            vec![
+                (0, Shape::Point((64, 128).into())),
+                (0, Shape::Point((-10, 128).into()))
+            ]
+        );
+        // padding follows largest radius of data driven style
+        let spec = StyleSpec::from_json(r#"{"radius": ["step", ["zoom"], 5, 1, 25]}"#).unwrap();
+        layer.style_spec = Some(std::rc::Rc::new(spec));
+        assert_eq!(layer.view_shapes(&shapes, &vw, &vw).count(), 3);
+        assert_eq!(layer.renderer, Renderer::Svg);
+    }
+
+    #[test]
+    fn test_heatmap_1() {
+        let vw = Viewport::new(&(0.0, 0.0).into(), (64, 64), 10);
+        let mut layer = HeatmapLayer::new(vec![
+            HeatPoint {
+                position: vw.center(),
+                weight: 1.0,
+            },
+            HeatPoint {
+                position: (90.0, 0.0).into(),
+                weight: 1.0,
+            },
+        ]);
+        layer.radius = Radius::Pixels(10.0);
+        layer.blur = 0.5;
+        let grid = layer.intensity(&vw, 1.0);
+        assert_eq!(grid.len(), 64 * 64);
+        // full intensity inside inner radius, fading out to radius
+        assert_eq!(grid[32 * 64 + 32], 1.0);
+        assert_eq!(grid[32 * 64 + 37], 1.0);
+        assert!(grid[32 * 64 + 40] > 0.0 && grid[32 * 64 + 40] < 0.5);
+        assert_eq!(grid[32 * 64 + 42], 0.0);
+        assert_eq!(grid.iter().filter(|v| **v > 0.0).count(), 305);
+
+        let image = layer.image(&vw, 1.0);
+        assert_eq!(image.len(), 64 * 64 * 4);
+        // last gradient color with layer opacity
+        assert_eq!(&image[(32 * 64 + 32) * 4..][..4], &[255, 0, 0, 204]);
+        assert_eq!(&image[..4], &[0, 0, 255, 0]);
+
+        // device pixels on high-dpi screens, radius scaled with image
+        let grid = layer.intensity(&vw, 2.0);
+        assert_eq!(HeatmapLayer::image_size(&vw, 2.0), (128, 128));
+        assert_eq!(grid.len(), 128 * 128);
+        assert_eq!(grid[64 * 128 + 74], 1.0);
+        assert_eq!(grid[64 * 128 + 84], 0.0);
+
+        // negative weights do not index out of palette
+        let negative = HeatmapLayer::new(vec![HeatPoint {
+            position: vw.center(),
+            weight: -1.0,
+        }]);
+        assert_eq!(
+            &negative.image(&vw, 1.0)[(32 * 64 + 32) * 4..][..4],
+            &[0, 0, 255, 0]
+        );
+
+        // drawn region covers viewport panned up to half of its size
+        let region = HeatmapLayer::region(&vw);
+        assert_eq!(region.size(), (128, 128).into());
+        assert_eq!(vw.point_offset(&region.center()), (32, 32).into());
+        let panned = |dx: i64, dy: i64| {
+            let (nw, se) = vw.pixel_bounds();
+            let d: Px = (dx, dy).into();
+            Viewport::from_pixel_bounds(nw.translate(&d), se.translate(&d), vw.z)
+        };
+        assert!(HeatmapLayer::covers(&region, &panned(-32, 32)));
+        assert!(!HeatmapLayer::covers(&region, &panned(33, 0)));
+        assert!(!HeatmapLayer::covers(&region, &panned(0, -33)));
+        assert!(!HeatmapLayer::covers(
+            &region,
+            &Viewport::new(&vw.center(), (64, 64), 11)
+        ));
+
+        // radius in meters follows ground resolution
+        layer.radius = Radius::Meters(1000.0);
+        let r = layer.radius_px(&vw);
+        assert!((r - 1000.0 / 152.87).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_mvt_1() {
+        // length delimited protobuf field
+        let field = |key: u8, data: &[u8]| [&[key, data.len() as u8], data].concat();
+        let line = [
+            &[0x08, 7][..],
+            &field(0x12, &[0, 0]),
+            &[0x18, 2],
+            &field(0x22, &[9, 4, 4, 18, 6, 0, 0, 6]),
+        ]
+        .concat();
+        let polygon = [
+            &[0x18, 3][..],
+            &field(
+                0x22,
+                &[
+                    9, 0, 0, 26, 20, 0, 0, 20, 19, 0, 15, 9, 4, 15, 26, 0, 12, 12, 0, 0, 11, 15,
+                ],
+            ),
+        ]
+        .concat();
+        let roads = [
+            &field(0x0a, b"roads")[..],
+            &field(0x12, &line),
+            &field(0x1a, b"kind"),
+            &field(0x22, &field(0x0a, b"major")),
+            &[0x28, 0x80, 0x20],
+        ]
+        .concat();
+        let water = [&field(0x0a, b"water")[..], &field(0x12, &polygon)].concat();
+        let data = [field(0x1a, &roads), field(0x1a, &water)].concat();
+
+        let tile = VectorTile::decode(&data).unwrap();
+        let roads = tile.layer("roads").unwrap();
+        assert_eq!(roads.extent, 4096);
+        let f = &roads.features[0];
+        assert_eq!(f.id, Some(7));
+        assert_eq!(f.kind, GeomType::LineString);
+        assert_eq!(f.properties["kind"], "major");
+        assert_eq!(f.geometry, vec![vec![[2, 2], [5, 2], [5, 5]]]);
+
+        // exterior ring with a hole, scaled and moved to tile origin
+        let f = &tile.layer("water").unwrap().features[0];
+        match &f.shapes(&(100, 100).into(), 2.0)[..] {
+            [Shape::Polygon(rings)] => {
+                assert_eq!(rings.len(), 2);
+                assert_eq!(rings[0][2], (120, 120).into());
+                assert_eq!(rings[1][0], (104, 104).into());
+                assert_eq!(rings[1].first(), rings[1].last());
+            }
+            s => panic!("expected single polygon, got {:?}", s),
+        }
+
+        assert_eq!(
+            VectorTile::decode(&data[..data.len() - 1]),
+            Err(MvtError::UnexpectedEnd)
+        );
+    }
+
+    #[test]
+    fn test_hit_1() {
+        let poly = |x: i64, y: i64, s: i64| -> Vec<Px> {
+            vec![
+                (x, y).into(),
+                (x + s, y).into(),
+                (x + s, y + s).into(),
+                (x, y + s).into(),
+                (x, y).into(),
+            ]
+        };
+        let mut layer = GeoJsonLayer::default();
+        layer.style.radius = 5.0;
+        layer.style.stroke_width = 2.0;
+        let shapes = vec![
+            vec![Shape::Polygon(vec![poly(0, 0, 100), poly(40, 40, 20)])],
+            vec![Shape::Line(vec![(0, 50).into(), (100, 50).into()])],
+            vec![Shape::Point((200, 200).into())],
+        ];
+        // topmost feature wins
+        assert_eq!(layer.hit(&shapes, 0, &(50, 51).into(), 0.0), Some(1));
+        assert_eq!(layer.hit(&shapes, 0, &(10, 10).into(), 0.0), Some(0));
+        // holes are not part of polygon, but their edges are
+        assert_eq!(layer.hit(&shapes, 0, &(50, 42).into(), 0.0), None);
+        assert_eq!(layer.hit(&shapes, 0, &(50, 42).into(), 3.0), Some(0));
+        // point radius with half of stroke
+        assert_eq!(layer.hit(&shapes, 0, &(206, 200).into(), 0.0), Some(2));
+        assert_eq!(layer.hit(&shapes, 0, &(207, 200).into(), 0.0), None);
+        assert_eq!(layer.hit(&shapes, 0, &(150, 150).into(), 3.0), None);
+    }
+
+    #[test]
+    fn test_style_spec_1() {
+        let spec = StyleSpec::from_json(
+            r##"{
+                "stroke": ["match", ["get", "kind"], "road", "#f00", ["path", "track"], "#0f0", "#000"],
+                "stroke-width": ["interpolate", ["linear"], ["zoom"], 10, 1, 14, 5],
+                "radius": ["step", ["get", "pop"], 2, 1000, 4, 10000, 8],
+                "dash": [4, 2]
+            }"##,
+        )
+        .unwrap();
+        let base = Style::default();
+        let props = |json: &str| serde_json::from_str::<serde_json::Map<_, _>>(json).unwrap();
+
+        let style = spec.resolve(
+            &base,
+            12.0,
+            Some(&props(r#"{"kind": "track", "pop": 5000}"#)),
+        );
+        assert_eq!(style.stroke, "#0f0");
+        assert_eq!(style.stroke_width, 3.0);
+        assert_eq!(style.radius, 4.0);
+        assert_eq!(style.dash, vec![4.0, 2.0]);
+        assert_eq!(style.fill, base.fill);
+        // stops are clamped and missing properties fall back
+        let style = spec.resolve(&base, 20.0, None);
+        assert_eq!(style.stroke, "#000");
+        assert_eq!(style.stroke_width, 5.0);
+        assert_eq!(style.radius, base.radius);
+        let style = spec.resolve(&base, 0.0, Some(&props(r#"{"kind": "road", "pop": 1}"#)));
+        assert_eq!((style.stroke.as_str(), style.stroke_width), ("#f00", 1.0));
+        assert_eq!(style.radius, 2.0);
+        // numeric labels match integer and float properties
+        let spec = StyleSpec::from_json(
+            r##"{"stroke": ["match", ["get", "lanes"], 1, "#f00", [2, 3.5], "#0f0", "#000"]}"##,
+        )
+        .unwrap();
+        let stroke = |json: &str| spec.resolve(&base, 0.0, Some(&props(json))).stroke;
+        assert_eq!(stroke(r#"{"lanes": 1.0}"#), "#f00");
+        assert_eq!(stroke(r#"{"lanes": 1}"#), "#f00");
+        assert_eq!(stroke(r#"{"lanes": 2.0}"#), "#0f0");
+        assert_eq!(stroke(r#"{"lanes": 3.5}"#), "#0f0");
+        assert_eq!(stroke(r#"{"lanes": "1"}"#), "#000");
+
+        // loadable with serde
+        let spec: StyleSpec = serde_json::from_str(r#"{"fill-opacity": 0.5}"#).unwrap();
+        assert_eq!(spec.resolve(&base, 0.0, None).fill_opacity, 0.5);
+
+        let err = |json: &str| StyleSpec::from_json(json).unwrap_err();
+        assert_eq!(
+            err(r##"{"color": "#fff"}"##),
+            StyleError::Unknown("color".into())
+        );
+        assert_eq!(err("[]"), StyleError::NotObject);
+        assert_eq!(
+            err(r#"{"fill": 3}"#).to_string(),
+            "invalid style at fill: expected color string, got 3"
+        );
+        assert_eq!(
+            err(r#"{"radius": ["interpolate", ["linear"], ["zoom"], 5, 1, 3, 2]}"#),
+            StyleError::Invalid(
+                "radius".into(),
+                "stop inputs must be in ascending order".into()
+            )
+        );
+        assert_eq!(
+            err(r##"{"fill": ["match", ["get", "a"], 1, 2, "#fff"]}"##),
+            StyleError::Invalid("fill[case 0]".into(), "expected color string, got 2".into())
+        );
+        match err(r##"{"stroke": ["coalesce", "#fff"]}"##) {
+            StyleError::Invalid(_, msg) => assert_eq!(msg, "unknown expression 'coalesce'"),
+            e => panic!("unexpected error {:?}", e),
+        }
+        match err(r#"{"stroke": ["get", "a"]"#) {
+            StyleError::Json(_) => (),
+            e => panic!("unexpected error {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_feature_style_1() {
+        let mut layer = GeoJsonLayer::from_json(
+            r#"{"type": "FeatureCollection", "features": [
+                {"type": "Feature", "properties": {"size": 10},
+                    "geometry": {"type": "Point", "coordinates": [0, 0]}},
+                {"type": "Feature", "properties": null,
+                    "geometry": {"type": "Point", "coordinates": [0, 0]}}]}"#,
+        )
+        .unwrap();
+        layer.style.stroke_width = 0.0;
+        assert_eq!(layer.feature_style(0, 3).radius, 5.0);
+        let spec = StyleSpec::from_json(r#"{"radius": ["get", "size"]}"#).unwrap();
+        layer.style_spec = Some(std::rc::Rc::new(spec));
+        assert_eq!(layer.feature_style(0, 3).radius, 10.0);
+        assert_eq!(layer.feature_style(1, 3).radius, 5.0);
+        // hit radius follows feature style
+        let shapes = vec![
+            vec![Shape::Point((0, 0).into())],
+            vec![Shape::Point((100, 0).into())],
+        ];
+        assert_eq!(layer.hit(&shapes, 3, &(8, 0).into(), 0.0), Some(0));
+        assert_eq!(layer.hit(&shapes, 3, &(108, 0).into(), 0.0), None);
+    }
+
+    #[test]
+    fn test_scale_bar_1() {
+        assert_eq!(scale::round_down(7.3), 5.0);
+        assert_eq!(scale::round_down(1999.0), 1000.0);
+        assert_eq!(scale::round_down(0.27), 0.2);
+        assert_eq!(scale::round_down(0.0), 0.0);
+
+        let bar = ScaleBar::metric(10.0, 80.0);
+        assert_eq!(bar.label, "500 m");
+        assert_eq!(bar.width, 50.0);
+        assert_eq!(ScaleBar::metric(10.0, 100.0).label, "1 km");
+        let bar = ScaleBar::metric(100.0, 100.0);
+        assert_eq!((bar.label.as_str(), bar.width), ("10 km", 100.0));
+        let bar = ScaleBar::imperial(10.0, 100.0);
+        assert_eq!(bar.label, "2000 ft");
+        assert!((bar.width - 60.96).abs() < 1e-9);
+        let bar = ScaleBar::imperial(100.0, 100.0);
+        assert_eq!(bar.label, "5 mi");
+
+        // scale shrinks towards poles
+        let equator = LonLat { lon: 0.0, lat: 0.0 }.meters_per_pixel(10);
+        let north = LonLat {
+            lon: 0.0,
+            lat: 60.0,
+        }
+        .meters_per_pixel(10);
+        assert!((north / equator - 0.5).abs() < 1e-9);
+        assert_eq!(ScaleBar::metric(equator, 100.0).label, "10 km");
+        assert_eq!(ScaleBar::metric(north, 100.0).label, "5 km");
+    }
+
+    #[test]
+    fn test_attributions_1() {
+        let tile = |attribution: &str, visible: bool| TileLayer {
+            attribution: Some(attribution.into()),
+            visible,
+            ..TileLayer::new("https://tile.example.org/{z}/{x}/{y}.png").unwrap()
+        };
+        let tiles = vec![
+            tile("&copy; OpenStreetMap", true),
+            tile("Hidden", false),
+            tile("", true),
+        ];
+        let vector_tiles = vec![VectorTileLayer::new(
+            tile("&copy; OpenStreetMap", true),
+            vec![],
+        )];
+        let geojson = GeoJsonLayer {
+            attribution: Some("Data".into()),
+            visible: true,
+            ..GeoJsonLayer::default()
+        };
+        let heatmap = HeatmapLayer {
+            attribution: Some("Heat".into()),
+            visible: false,
+            ..HeatmapLayer::default()
+        };
+
+        // duplicates, empty and hidden layer credits are left out
+        assert_eq!(
+            attributions(&tiles, &vector_tiles, &[geojson], &[heatmap]),
+            vec!["&copy; OpenStreetMap".to_string(), "Data".to_string()]
+        );
+        assert!(attributions(&[], &[], &[], &[]).is_empty());
+    }
+
+    #[test]
+    fn test_coord_format_1() {
+        let ll = LonLat {
+            lon: 24.941_667,
+            lat: 60.170_833,
+        };
+        assert_eq!(CoordFormat::Decimal.format(&ll), "60.17083, 24.94167");
+        assert_eq!(CoordFormat::Dms.format(&ll), "60°10′15.0″N 24°56′30.0″E");
+        let ll = LonLat {
+            lon: -0.999_99,
+            lat: -33.5,
+        };
+        // seconds rounding carries over to minutes and degrees
+        assert_eq!(CoordFormat::Dms.format(&ll), "33°30′00.0″S 1°00′00.0″W");
+
+        let (x, y) = LonLat {
+            lon: 180.0,
+            lat: 0.0,
+        }
+        .mercator();
+        assert!((x - PI * EARTH_RADIUS).abs() < 1e-6 && y.abs() < 1e-6);
+        let ll = LonLat {
+            lon: 0.0,
+            lat: 85.051_128_78,
+        };
+        assert!((ll.mercator().1 - PI * EARTH_RADIUS).abs() < 1.0);
+        assert_eq!(
+            CoordFormat::Meters.format(&LonLat { lon: 1.0, lat: 0.0 }),
+            "111319 E, 0 N"
+        );
+    }
+
+    #[test]
+    fn test_geolocation_1() {
+        let fix = |accuracy: f64| Location {
+            position: LonLat::default(),
+            accuracy,
+        };
+        // accuracy circle radius
+        let equator_mpp = LonLat::default().meters_per_pixel(0);
+        assert!((fix(equator_mpp * 8.0).accuracy_px(0) - 8.0).abs() < 1e-9);
+        assert!((fix(equator_mpp * 8.0).accuracy_px(2) - 32.0).abs() < 1e-9);
+        assert_eq!(
+            GeolocationError::from_code(1).to_string(),
+            "geolocation permission denied"
+        );
+        assert!(GeolocationError::from_code(1).is_permanent());
+        assert!(!GeolocationError::from_code(3).is_permanent());
+    }
+
+    #[test]
+    fn test_measure_1() {
+        let ll = |lon: f64, lat: f64| LonLat { lon, lat };
+        let mut m = Measurement::new(MeasureKind::Distance);
+        m.add(ll(0.0, 0.0));
+        // double click adds the same vertex twice
+        m.add(ll(1.0, 0.0));
+        m.add(ll(1.0, 0.0));
+        assert_eq!(m.vertices.len(), 2);
+        // one degree of longitude on the equator of WGS84 ellipsoid
+        let segments = m.segments(None);
+        assert_eq!(segments.len(), 1);
+        assert!((segments[0] - 111_319.49).abs() < 0.01);
+        // preview follows cursor until finished
+        let cursor = ll(1.0, 1.0);
+        assert_eq!(m.segments(Some(&cursor)).len(), 2);
+        assert!(m.distance(Some(&cursor)) > 200_000.0);
+        m.finish();
+        m.add(ll(2.0, 0.0));
+        assert_eq!(m.points(Some(&cursor)), vec![ll(0.0, 0.0), ll(1.0, 0.0)]);
+        assert_eq!(m.area(None), 0.0);
+
+        // one degree square on the equator
+        let mut m = Measurement::new(MeasureKind::Area);
+        for &(lon, lat) in &[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0)] {
+            m.add(ll(lon, lat));
+        }
+        let area = m.area(Some(&ll(0.0, 1.0)));
+        let expected = (MEAN_EARTH_RADIUS * PI / 180.0).powi(2);
+        assert!((area / expected - 1.0).abs() < 1e-4);
+        // closing segment counts in perimeter
+        assert_eq!(m.segments(Some(&ll(0.0, 1.0))).len(), 4);
+        // winding order does not matter
+        let ring = [
+            ll(0.0, 0.0),
+            ll(0.0, 1.0),
+            ll(1.0, 1.0),
+            ll(1.0, 0.0),
+            ll(0.0, 0.0),
+        ];
+        assert!((measure::geodesic_area(&ring) / expected - 1.0).abs() < 1e-4);
+        // ring crossing antimeridian
+        let ring = [
+            ll(179.5, 0.0),
+            ll(179.5, 1.0),
+            ll(-179.5, 1.0),
+            ll(-179.5, 0.0),
+        ];
+        assert!((measure::geodesic_area(&ring) / expected - 1.0).abs() < 1e-4);
+
+        assert_eq!(measure::format_distance(532.4), "532 m");
+        assert_eq!(measure::format_distance(1234.0), "1.23 km");
+        assert_eq!(measure::format_distance(250_600.0), "251 km");
+        assert_eq!(measure::format_area(530.0), "530 m²");
+        assert_eq!(measure::format_area(12_500.0), "1.25 ha");
+        assert_eq!(measure::format_area(2_500_000.0), "2.50 km²");
+    }
+
     // #[test]
     // fn test_pixel_offset_1() {
     //     let vw = Viewport {
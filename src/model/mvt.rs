@@ -0,0 +1,409 @@
+use super::{Px, Shape, Style, StyleSpec, TileLayer};
+use failure::Fail;
+use serde_json::{Map, Number, Value};
+use std::fmt;
+use std::rc::Rc;
+
+/// Mapbox vector tile
+/// https://github.com/mapbox/vector-tile-spec/tree/master/2.1
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct VectorTile {
+    pub layers: Vec<MvtLayer>,
+}
+
+/// Named layer of vector tile
+#[derive(Debug, PartialEq, Clone)]
+pub struct MvtLayer {
+    pub name: String,
+    /// Tile width and height in geometry coordinates
+    pub extent: u32,
+    pub features: Vec<MvtFeature>,
+}
+
+/// Geometry type of vector tile feature
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum GeomType {
+    Unknown,
+    Point,
+    LineString,
+    Polygon,
+}
+
+/// Feature of vector tile layer
+#[derive(Debug, PartialEq, Clone)]
+pub struct MvtFeature {
+    pub id: Option<u64>,
+    pub kind: GeomType,
+    pub properties: Map<String, Value>,
+    /// Points, lines or rings in tile coordinates, rings are closed
+    pub geometry: Vec<Vec<[i32; 2]>>,
+}
+
+/// Error from decoding vector tile
+#[derive(Debug, PartialEq)]
+pub enum MvtError {
+    /// Data ended in the middle of a value
+    UnexpectedEnd,
+    /// Unsupported protobuf wire type
+    WireType(u8),
+    /// String is not valid utf-8
+    Utf8,
+    /// Unknown geometry command
+    Command(u32),
+    /// Feature tag refers to missing key or value
+    Tag(u32),
+}
+
+impl fmt::Display for MvtError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MvtError::UnexpectedEnd => write!(f, "unexpected end of vector tile data"),
+            MvtError::WireType(t) => write!(f, "unsupported protobuf wire type {}", t),
+            MvtError::Utf8 => write!(f, "invalid utf-8 string in vector tile"),
+            MvtError::Command(c) => write!(f, "unknown geometry command {}", c),
+            MvtError::Tag(t) => write!(f, "feature tag {} refers to missing key or value", t),
+        }
+    }
+}
+
+impl Fail for MvtError {}
+
+// protobuf wire types
+const VARINT: u8 = 0;
+const FIXED64: u8 = 1;
+const BYTES: u8 = 2;
+const FIXED32: u8 = 5;
+
+// minimal protobuf reader
+// https://developers.google.com/protocol-buffers/docs/encoding
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    // reads field number and wire type, none at end of data
+    fn field(&mut self) -> Result<Option<(u64, u8)>, MvtError> {
+        if self.pos >= self.buf.len() {
+            return Ok(None);
+        }
+        let key = self.varint()?;
+        Ok(Some((key >> 3, (key & 0x7) as u8)))
+    }
+
+    fn varint(&mut self) -> Result<u64, MvtError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = *self.buf.get(self.pos).ok_or(MvtError::UnexpectedEnd)?;
+            self.pos += 1;
+            value |= u64::from(b & 0x7f) << shift;
+            if b & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Ok(value)
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], MvtError> {
+        let end = self.pos.checked_add(n).ok_or(MvtError::UnexpectedEnd)?;
+        let bytes = self.buf.get(self.pos..end).ok_or(MvtError::UnexpectedEnd)?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], MvtError> {
+        let n = self.varint()? as usize;
+        self.take(n)
+    }
+
+    fn string(&mut self) -> Result<String, MvtError> {
+        String::from_utf8(self.bytes()?.to_vec()).map_err(|_| MvtError::Utf8)
+    }
+
+    fn fixed32(&mut self) -> Result<[u8; 4], MvtError> {
+        let mut b = [0; 4];
+        b.copy_from_slice(self.take(4)?);
+        Ok(b)
+    }
+
+    fn fixed64(&mut self) -> Result<[u8; 8], MvtError> {
+        let mut b = [0; 8];
+        b.copy_from_slice(self.take(8)?);
+        Ok(b)
+    }
+
+    // reads packed repeated varints
+    fn packed(&mut self) -> Result<Vec<u32>, MvtError> {
+        let mut r = Reader::new(self.bytes()?);
+        let mut values = vec![];
+        while r.pos < r.buf.len() {
+            values.push(r.varint()? as u32);
+        }
+        Ok(values)
+    }
+
+    fn skip(&mut self, wire_type: u8) -> Result<(), MvtError> {
+        match wire_type {
+            VARINT => self.varint().map(|_| ()),
+            FIXED64 => self.take(8).map(|_| ()),
+            BYTES => self.bytes().map(|_| ()),
+            FIXED32 => self.take(4).map(|_| ()),
+            t => Err(MvtError::WireType(t)),
+        }
+    }
+}
+
+fn zigzag(n: u64) -> i64 {
+    ((n >> 1) as i64) ^ -((n & 1) as i64)
+}
+
+impl VectorTile {
+    /// Decodes vector tile from protobuf data
+    pub fn decode(data: &[u8]) -> Result<Self, MvtError> {
+        let mut r = Reader::new(data);
+        let mut layers = vec![];
+        while let Some((field, wire_type)) = r.field()? {
+            match (field, wire_type) {
+                (3, BYTES) => layers.push(MvtLayer::decode(r.bytes()?)?),
+                (_, t) => r.skip(t)?,
+            }
+        }
+        Ok(Self { layers })
+    }
+
+    /// Returns layer by name
+    pub fn layer(&self, name: &str) -> Option<&MvtLayer> {
+        self.layers.iter().find(|l| l.name == name)
+    }
+}
+
+impl MvtLayer {
+    fn decode(data: &[u8]) -> Result<Self, MvtError> {
+        let mut r = Reader::new(data);
+        let (mut name, mut extent) = (String::new(), 4096);
+        let (mut keys, mut values, mut features) = (vec![], vec![], vec![]);
+        while let Some((field, wire_type)) = r.field()? {
+            match (field, wire_type) {
+                (1, BYTES) => name = r.string()?,
+                (2, BYTES) => features.push(r.bytes()?),
+                (3, BYTES) => keys.push(r.string()?),
+                (4, BYTES) => values.push(Self::decode_value(r.bytes()?)?),
+                (5, VARINT) => extent = r.varint()? as u32,
+                (_, t) => r.skip(t)?,
+            }
+        }
+        // features are decoded last, keys and values may follow them
+        let features = features
+            .into_iter()
+            .map(|f| MvtFeature::decode(f, &keys, &values))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            name,
+            extent,
+            features,
+        })
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Value, MvtError> {
+        let mut r = Reader::new(data);
+        let mut value = Value::Null;
+        while let Some((field, wire_type)) = r.field()? {
+            value = match (field, wire_type) {
+                (1, BYTES) => Value::String(r.string()?),
+                (2, FIXED32) => float(f64::from(f32::from_bits(u32::from_le_bytes(r.fixed32()?)))),
+                (3, FIXED64) => float(f64::from_bits(u64::from_le_bytes(r.fixed64()?))),
+                (4, VARINT) => Value::from(r.varint()? as i64),
+                (5, VARINT) => Value::from(r.varint()?),
+                (6, VARINT) => Value::from(zigzag(r.varint()?)),
+                (7, VARINT) => Value::Bool(r.varint()? != 0),
+                (_, t) => {
+                    r.skip(t)?;
+                    continue;
+                }
+            };
+        }
+        Ok(value)
+    }
+}
+
+// json has no representation for nan or infinity
+fn float(f: f64) -> Value {
+    Number::from_f64(f)
+        .map(Value::Number)
+        .unwrap_or(Value::Null)
+}
+
+// geometry commands
+const MOVE_TO: u32 = 1;
+const LINE_TO: u32 = 2;
+const CLOSE_PATH: u32 = 7;
+
+impl MvtFeature {
+    fn decode(data: &[u8], keys: &[String], values: &[Value]) -> Result<Self, MvtError> {
+        let mut r = Reader::new(data);
+        let mut feature = MvtFeature {
+            id: None,
+            kind: GeomType::Unknown,
+            properties: Map::new(),
+            geometry: vec![],
+        };
+        while let Some((field, wire_type)) = r.field()? {
+            match (field, wire_type) {
+                (1, VARINT) => feature.id = Some(r.varint()?),
+                (2, BYTES) => {
+                    for pair in r.packed()?.chunks(2) {
+                        let (k, v) = match *pair {
+                            [k, v] => (k, v),
+                            [k] => return Err(MvtError::Tag(k)),
+                            _ => continue,
+                        };
+                        let key = keys.get(k as usize).ok_or(MvtError::Tag(k))?;
+                        let value = values.get(v as usize).ok_or(MvtError::Tag(v))?;
+                        feature.properties.insert(key.clone(), value.clone());
+                    }
+                }
+                (3, VARINT) => {
+                    feature.kind = match r.varint()? {
+                        1 => GeomType::Point,
+                        2 => GeomType::LineString,
+                        3 => GeomType::Polygon,
+                        _ => GeomType::Unknown,
+                    }
+                }
+                (4, BYTES) => feature.geometry = Self::decode_geometry(&r.packed()?)?,
+                (_, t) => r.skip(t)?,
+            }
+        }
+        Ok(feature)
+    }
+
+    // runs geometry commands with a cursor, each move starts a new part
+    fn decode_geometry(commands: &[u32]) -> Result<Vec<Vec<[i32; 2]>>, MvtError> {
+        let mut parts: Vec<Vec<[i32; 2]>> = vec![];
+        let mut cursor = [0i32, 0i32];
+        let mut i = 0;
+        while i < commands.len() {
+            let (id, count) = (commands[i] & 0x7, commands[i] >> 3);
+            i += 1;
+            match id {
+                MOVE_TO | LINE_TO => {
+                    for _ in 0..count {
+                        let dx = commands.get(i).ok_or(MvtError::UnexpectedEnd)?;
+                        let dy = commands.get(i + 1).ok_or(MvtError::UnexpectedEnd)?;
+                        i += 2;
+                        cursor[0] += zigzag(u64::from(*dx)) as i32;
+                        cursor[1] += zigzag(u64::from(*dy)) as i32;
+                        match parts.last_mut() {
+                            Some(part) if id == LINE_TO => part.push(cursor),
+                            _ => parts.push(vec![cursor]),
+                        }
+                    }
+                }
+                CLOSE_PATH => {
+                    if let Some(part) = parts.last_mut() {
+                        if let Some(&first) = part.first() {
+                            part.push(first);
+                        }
+                    }
+                }
+                c => return Err(MvtError::Command(c)),
+            }
+        }
+        Ok(parts)
+    }
+
+    /// Projects geometry to pixels, given tile origin and pixels per tile coordinate
+    pub fn shapes(&self, origin: &Px, scale: f64) -> Vec<Shape> {
+        let px = |p: &[i32; 2]| -> Px {
+            (
+                origin.x + (f64::from(p[0]) * scale).round() as i64,
+                origin.y + (f64::from(p[1]) * scale).round() as i64,
+            )
+                .into()
+        };
+        let line = |part: &Vec<[i32; 2]>| part.iter().map(px).collect::<Vec<Px>>();
+        match self.kind {
+            GeomType::Unknown => vec![],
+            GeomType::Point => self
+                .geometry
+                .iter()
+                .flatten()
+                .map(|p| Shape::Point(px(p)))
+                .collect(),
+            GeomType::LineString => self.geometry.iter().map(|l| Shape::Line(line(l))).collect(),
+            GeomType::Polygon => {
+                // exterior rings have positive area, holes follow their exterior ring
+                let mut polygons: Vec<Vec<Vec<Px>>> = vec![];
+                for ring in &self.geometry {
+                    let area = ring_area(ring);
+                    if area > 0.0 || polygons.is_empty() {
+                        polygons.push(vec![line(ring)]);
+                    } else if area < 0.0 {
+                        if let Some(polygon) = polygons.last_mut() {
+                            polygon.push(line(ring));
+                        }
+                    }
+                }
+                polygons.into_iter().map(Shape::Polygon).collect()
+            }
+        }
+    }
+}
+
+// surveyor's formula, positive for clockwise rings with y axis down
+fn ring_area(ring: &[[i32; 2]]) -> f64 {
+    ring.windows(2)
+        .map(|w| f64::from(w[0][0]) * f64::from(w[1][1]) - f64::from(w[1][0]) * f64::from(w[0][1]))
+        .sum::<f64>()
+        / 2.0
+}
+
+/// Draws features of a vector tile layer with given style
+#[derive(Debug, PartialEq, Clone)]
+pub struct StyleRule {
+    /// Source layer name in vector tiles
+    pub layer: String,
+    pub style: Style,
+    /// Data driven style overriding `style` per feature
+    pub style_spec: Option<StyleSpec>,
+}
+
+impl StyleRule {
+    /// Creates rule drawing all features of source layer with style
+    pub fn new(layer: &str, style: Style) -> Self {
+        Self {
+            layer: layer.into(),
+            style,
+            style_spec: None,
+        }
+    }
+}
+
+/// Vector tile layer drawn on top of tile grid
+///
+/// Features are drawn only, unlike GeoJSON features they are not hit-tested
+/// for click and hover events.
+#[derive(PartialEq, Clone)]
+pub struct VectorTileLayer {
+    /// Tile source, tiles are fetched as protobuf, attribution is taken from source
+    pub source: TileLayer,
+    /// Rules are drawn in order, layers without a rule are not drawn
+    pub rules: Rc<Vec<StyleRule>>,
+    /// Controls whether layer is visible on map
+    pub visible: bool,
+}
+
+impl VectorTileLayer {
+    /// Creates layer from tile source and style rules
+    pub fn new(source: TileLayer, rules: Vec<StyleRule>) -> Self {
+        Self {
+            source,
+            rules: Rc::new(rules),
+            visible: true,
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use super::{LonLat, Px};
+
+/// Popup anchored to map coordinates
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Popup {
+    /// Popup position
+    pub position: LonLat,
+    /// Html content inserted unsanitized, map children are drawn as content if none is given
+    pub html: Option<String>,
+    /// Pixel offset of popup tip from its position
+    pub offset: Px,
+    /// Additional css class
+    pub class: String,
+}
+
+impl Popup {
+    /// Creates popup with html content on given position
+    pub fn new(position: LonLat, html: &str) -> Self {
+        Self {
+            position,
+            html: Some(html.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Returns pixel amount to pan the map by so that popup box
+    /// at `nw` with `size` fits inside container of `container_size`,
+    /// leaving `padding` pixels between the box and container edges
+    pub fn autopan_offset(nw: Px, size: Px, container_size: Px, padding: i64) -> Px {
+        // pans along single axis
+        let fit = |start: i64, len: i64, container: i64| -> i64 {
+            let end = start + len;
+            if start < padding {
+                padding - start
+            } else if end > container - padding {
+                // keep start of the box visible if box is larger than container
+                (container - padding - end).max(padding - start)
+            } else {
+                0
+            }
+        };
+        Px {
+            x: fit(nw.x, size.x, container_size.x),
+            y: fit(nw.y, size.y, container_size.y),
+        }
+    }
+}
@@ -1,6 +1,16 @@
 use googleprojection as wgs84;
 
-#[derive(Clone, Default, PartialEq, Copy)]
+/// Earth radius used by spherical mercator, in meters
+pub const EARTH_RADIUS: f64 = 6_378_137.0;
+
+/// Mean earth radius used for great-circle calculations, in meters
+pub const MEAN_EARTH_RADIUS: f64 = 6_371_008.8;
+
+// WGS84 ellipsoid semi-major axis and flattening
+const WGS84_A: f64 = 6_378_137.0;
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+#[derive(Debug, Clone, Default, PartialEq, Copy)]
 pub struct LonLat {
     pub lon: f64,
     pub lat: f64,
@@ -15,26 +25,210 @@ impl LonLat {
         }
     }
 
-    // pub fn distance(&self, other: &Self) -> Self {
-    //     Self {
-    //         lon: self.lon - other.lon,
-    //         lat: self.lat - other.lat,
-    //     }
-    // }
-
-    // pub fn avg(&self, other: &Self) -> Self {
-    //     Self {
-    //         lon: self.lon / 2.0 + other.lon / 2.0,
-    //         lat: self.lat / 2.0 + other.lat / 2.0,
-    //     }
-    // }
-
-    // pub fn translate(&self, other: &Self) -> Self {
-    //     Self {
-    //         lon: self.lon + other.lon,
-    //         lat: self.lat + other.lat,
-    //     }
-    // }
+    /// Returns ground resolution in meters per pixel at this latitude on zoom level `z`
+    pub fn meters_per_pixel(&self, z: usize) -> f64 {
+        2.0 * std::f64::consts::PI * EARTH_RADIUS * self.lat.to_radians().cos()
+            / (256.0 * 2f64.powi(z as i32))
+    }
+
+    /// Returns spherical mercator (EPSG:3857) coordinates in meters
+    pub fn mercator(&self) -> (f64, f64) {
+        let x = EARTH_RADIUS * self.lon.to_radians();
+        let y = EARTH_RADIUS
+            * (std::f64::consts::FRAC_PI_4 + self.lat.to_radians() / 2.0)
+                .tan()
+                .ln();
+        (x, y)
+    }
+
+    /// Returns great-circle distance in meters, using haversine formula
+    pub fn distance(&self, other: &Self) -> f64 {
+        let (phi1, phi2) = (self.lat.to_radians(), other.lat.to_radians());
+        let dphi = phi2 - phi1;
+        let dlambda = (other.lon - self.lon).to_radians();
+
+        let a =
+            (dphi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (dlambda / 2.0).sin().powi(2);
+        2.0 * a.sqrt().atan2((1.0 - a).sqrt()) * MEAN_EARTH_RADIUS
+    }
+
+    /// Returns distance in meters on WGS84 ellipsoid, using Vincenty's inverse formula.
+    /// Returns None if formula does not converge, which may happen for nearly antipodal points.
+    /// https://en.wikipedia.org/wiki/Vincenty%27s_formulae
+    pub fn vincenty_distance(&self, other: &Self) -> Option<f64> {
+        let (a, f) = (WGS84_A, WGS84_F);
+        let b = a * (1.0 - f);
+
+        let l = (other.lon - self.lon).to_radians();
+        // reduced latitudes
+        let u1 = ((1.0 - f) * self.lat.to_radians().tan()).atan();
+        let u2 = ((1.0 - f) * other.lat.to_radians().tan()).atan();
+        let (sin_u1, cos_u1) = u1.sin_cos();
+        let (sin_u2, cos_u2) = u2.sin_cos();
+
+        let mut lambda = l;
+        for _ in 0..200 {
+            let (sin_lambda, cos_lambda) = lambda.sin_cos();
+            let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+                + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2))
+            .sqrt();
+            if sin_sigma == 0.0 {
+                // coincident points
+                return Some(0.0);
+            }
+            let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+            let sigma = sin_sigma.atan2(cos_sigma);
+            let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+            let cos2_alpha = 1.0 - sin_alpha.powi(2);
+            // equatorial line has cos2_alpha of 0
+            let cos_2sigma_m = if cos2_alpha != 0.0 {
+                cos_sigma - 2.0 * sin_u1 * sin_u2 / cos2_alpha
+            } else {
+                0.0
+            };
+            let c = f / 16.0 * cos2_alpha * (4.0 + f * (4.0 - 3.0 * cos2_alpha));
+
+            let lambda_prev = lambda;
+            lambda = l
+                + (1.0 - c)
+                    * f
+                    * sin_alpha
+                    * (sigma
+                        + c * sin_sigma
+                            * (cos_2sigma_m + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))));
+
+            if (lambda - lambda_prev).abs() < 1e-12 {
+                let u2 = cos2_alpha * (a * a - b * b) / (b * b);
+                let k1 = ((1.0 + u2).sqrt() - 1.0) / ((1.0 + u2).sqrt() + 1.0);
+                let big_a = (1.0 + k1 * k1 / 4.0) / (1.0 - k1);
+                let big_b = k1 * (1.0 - 3.0 / 8.0 * k1 * k1);
+                let delta_sigma = big_b
+                    * sin_sigma
+                    * (cos_2sigma_m
+                        + big_b / 4.0
+                            * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))
+                                - big_b / 6.0
+                                    * cos_2sigma_m
+                                    * (-3.0 + 4.0 * sin_sigma.powi(2))
+                                    * (-3.0 + 4.0 * cos_2sigma_m.powi(2))));
+                return Some(b * big_a * (sigma - delta_sigma));
+            }
+        }
+        None
+    }
+
+    /// Returns initial bearing towards other point in degrees, clockwise from north
+    pub fn bearing(&self, other: &Self) -> f64 {
+        let (phi1, phi2) = (self.lat.to_radians(), other.lat.to_radians());
+        let dlambda = (other.lon - self.lon).to_radians();
+
+        let y = dlambda.sin() * phi2.cos();
+        let x = phi1.cos() * phi2.sin() - phi1.sin() * phi2.cos() * dlambda.cos();
+        (y.atan2(x).to_degrees() + 360.0) % 360.0
+    }
+
+    /// Returns point reached when travelling given distance in meters
+    /// along great circle with given initial bearing in degrees
+    pub fn destination(&self, bearing: f64, distance: f64) -> Self {
+        let delta = distance / MEAN_EARTH_RADIUS;
+        let theta = bearing.to_radians();
+        let phi1 = self.lat.to_radians();
+        let lambda1 = self.lon.to_radians();
+
+        let phi2 = (phi1.sin() * delta.cos() + phi1.cos() * delta.sin() * theta.cos()).asin();
+        let lambda2 = lambda1
+            + (theta.sin() * delta.sin() * phi1.cos()).atan2(delta.cos() - phi1.sin() * phi2.sin());
+
+        Self {
+            lon: wrap_lon(lambda2.to_degrees()),
+            lat: phi2.to_degrees(),
+        }
+    }
+
+    /// Returns halfway point along great circle between points
+    pub fn midpoint(&self, other: &Self) -> Self {
+        self.interpolate(other, 0.5)
+    }
+
+    /// Returns point at given fraction along great circle between points,
+    /// 0.0 being this point and 1.0 the other
+    pub fn interpolate(&self, other: &Self, fraction: f64) -> Self {
+        let delta = self.distance(other) / MEAN_EARTH_RADIUS;
+        if delta == 0.0 {
+            return *self;
+        }
+        let (phi1, lambda1) = (self.lat.to_radians(), self.lon.to_radians());
+        let (phi2, lambda2) = (other.lat.to_radians(), other.lon.to_radians());
+
+        let a = ((1.0 - fraction) * delta).sin() / delta.sin();
+        let b = (fraction * delta).sin() / delta.sin();
+        // interpolate on cartesian coordinates
+        let x = a * phi1.cos() * lambda1.cos() + b * phi2.cos() * lambda2.cos();
+        let y = a * phi1.cos() * lambda1.sin() + b * phi2.cos() * lambda2.sin();
+        let z = a * phi1.sin() + b * phi2.sin();
+
+        Self {
+            lon: y.atan2(x).to_degrees(),
+            lat: z.atan2((x * x + y * y).sqrt()).to_degrees(),
+        }
+    }
+}
+
+/// Display formats of coordinates
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum CoordFormat {
+    /// Decimal degrees, latitude first
+    Decimal,
+    /// Degrees, minutes and seconds with hemisphere
+    Dms,
+    /// Spherical mercator meters, easting first
+    Meters,
+}
+
+impl Default for CoordFormat {
+    fn default() -> Self {
+        CoordFormat::Decimal
+    }
+}
+
+impl CoordFormat {
+    /// Short name of format
+    pub fn name(self) -> &'static str {
+        match self {
+            CoordFormat::Decimal => "DD",
+            CoordFormat::Dms => "DMS",
+            CoordFormat::Meters => "m",
+        }
+    }
+
+    /// Formats coordinates
+    pub fn format(self, ll: &LonLat) -> String {
+        match self {
+            CoordFormat::Decimal => format!("{:.5}, {:.5}", ll.lat, ll.lon),
+            CoordFormat::Dms => format!(
+                "{} {}",
+                dms(ll.lat, if ll.lat < 0.0 { 'S' } else { 'N' }),
+                dms(ll.lon, if ll.lon < 0.0 { 'W' } else { 'E' })
+            ),
+            CoordFormat::Meters => {
+                let (x, y) = ll.mercator();
+                // adding zero turns negative zero positive
+                format!("{:.0} E, {:.0} N", x.round() + 0.0, y.round() + 0.0)
+            }
+        }
+    }
+}
+
+// formats absolute degrees as degrees, minutes and seconds rounded to tenths
+fn dms(deg: f64, hemisphere: char) -> String {
+    let tenths = (deg.abs() * 36_000.0).round() as u64;
+    let (d, m, s) = (tenths / 36_000, tenths / 600 % 60, tenths % 600);
+    format!("{}°{:02}′{:02}.{}″{}", d, m, s / 10, s % 10, hemisphere)
+}
+
+// wraps longitude to range -180..180
+fn wrap_lon(lon: f64) -> f64 {
+    (lon + 540.0) % 360.0 - 180.0
 }
 
 impl Into<(f64, f64)> for LonLat {
@@ -52,7 +246,7 @@ impl From<(f64, f64)> for LonLat {
     }
 }
 
-#[derive(Clone, Default, PartialEq, Copy)]
+#[derive(Debug, Clone, Default, PartialEq, Copy)]
 pub struct Px {
     pub x: i64,
     pub y: i64,
@@ -0,0 +1,81 @@
+// meters in a foot and in a mile
+const FOOT: f64 = 0.3048;
+const MILE: f64 = 1609.344;
+
+/// Unit systems of scale bar
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Units {
+    Metric,
+    Imperial,
+    /// Metric bar above imperial bar
+    Both,
+}
+
+impl Default for Units {
+    fn default() -> Self {
+        Units::Metric
+    }
+}
+
+/// Scale bar of a round distance
+#[derive(Debug, PartialEq, Clone)]
+pub struct ScaleBar {
+    /// Bar width in pixels
+    pub width: f64,
+    /// Distance with unit, e.g. "200 km"
+    pub label: String,
+}
+
+impl ScaleBar {
+    /// Longest metric bar fitting in `max_width` pixels
+    pub fn metric(meters_per_pixel: f64, max_width: f64) -> Self {
+        let max = meters_per_pixel * max_width;
+        if max < 1000.0 {
+            Self::fit(max, 1.0, "m", meters_per_pixel)
+        } else {
+            Self::fit(max, 1000.0, "km", meters_per_pixel)
+        }
+    }
+
+    /// Longest imperial bar fitting in `max_width` pixels
+    pub fn imperial(meters_per_pixel: f64, max_width: f64) -> Self {
+        let max = meters_per_pixel * max_width;
+        if max < MILE {
+            Self::fit(max, FOOT, "ft", meters_per_pixel)
+        } else {
+            Self::fit(max, MILE, "mi", meters_per_pixel)
+        }
+    }
+
+    // picks round distance in unit not exceeding max meters
+    fn fit(max: f64, unit: f64, name: &str, meters_per_pixel: f64) -> Self {
+        let distance = round_down(max / unit);
+        Self {
+            width: distance * unit / meters_per_pixel,
+            label: format!("{} {}", distance, name),
+        }
+    }
+}
+
+/// Rounds down to 1, 2 or 5 times a power of ten
+pub fn round_down(x: f64) -> f64 {
+    if x <= 0.0 || !x.is_finite() {
+        return 0.0;
+    }
+    let pow = 10_f64.powf(x.log10().floor());
+    let d = x / pow;
+    let d = if d >= 5.0 {
+        5.0
+    } else if d >= 2.0 {
+        2.0
+    } else {
+        1.0
+    };
+    // fractions are rounded to avoid float noise in labels
+    if pow < 1.0 {
+        let digits = 10_f64.powf(-pow.log10().round());
+        (d * pow * digits).round() / digits
+    } else {
+        d * pow
+    }
+}
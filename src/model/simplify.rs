@@ -0,0 +1,184 @@
+use super::Px;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Line simplification applied to projected geometries
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Simplify {
+    Off,
+    /// Douglas–Peucker with maximum distance from original line in pixels
+    DouglasPeucker(f64),
+    /// Visvalingam–Whyatt with minimum triangle area in square pixels
+    Visvalingam(f64),
+}
+
+impl Default for Simplify {
+    fn default() -> Self {
+        Simplify::DouglasPeucker(1.0)
+    }
+}
+
+impl Simplify {
+    /// Simplifies line, first and last points are always kept
+    pub fn apply(&self, points: &[Px]) -> Vec<Px> {
+        let points = dedup(points);
+        match *self {
+            _ if points.len() < 3 => points,
+            Simplify::Off => points,
+            Simplify::DouglasPeucker(tolerance) => douglas_peucker(&points, tolerance),
+            Simplify::Visvalingam(area) => visvalingam(&points, area),
+        }
+    }
+}
+
+// removes consecutive duplicate points
+fn dedup(points: &[Px]) -> Vec<Px> {
+    let mut out: Vec<Px> = Vec::with_capacity(points.len());
+    for p in points {
+        if out.last() != Some(p) {
+            out.push(*p);
+        }
+    }
+    out
+}
+
+/// Squared distance from point p to segment a-b
+pub fn segment_dist_sq(p: &Px, a: &Px, b: &Px) -> f64 {
+    let (px, py) = (p.x as f64, p.y as f64);
+    let (ax, ay) = (a.x as f64, a.y as f64);
+    let (dx, dy) = (b.x as f64 - ax, b.y as f64 - ay);
+    let len_sq = dx * dx + dy * dy;
+    let t = if len_sq > 0.0 {
+        (((px - ax) * dx + (py - ay) * dy) / len_sq)
+            .max(0.0)
+            .min(1.0)
+    } else {
+        0.0
+    };
+    let (cx, cy) = (ax + t * dx - px, ay + t * dy - py);
+    cx * cx + cy * cy
+}
+
+/// Douglas–Peucker line simplification
+/// https://en.wikipedia.org/wiki/Ramer%E2%80%93Douglas%E2%80%93Peucker_algorithm
+pub fn douglas_peucker(points: &[Px], tolerance: f64) -> Vec<Px> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+    let tolerance_sq = tolerance * tolerance;
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+
+    // iterative to avoid deep recursion on long lines
+    let mut stack = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = stack.pop() {
+        let mut max_dist = 0.0;
+        let mut idx = first;
+        for i in first + 1..last {
+            let d = segment_dist_sq(&points[i], &points[first], &points[last]);
+            if d > max_dist {
+                max_dist = d;
+                idx = i;
+            }
+        }
+        if max_dist > tolerance_sq {
+            keep[idx] = true;
+            stack.push((first, idx));
+            stack.push((idx, last));
+        }
+    }
+
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(p, k)| if k { Some(*p) } else { None })
+        .collect()
+}
+
+// triangle area of point and its neighbours in visvalingam heap
+struct Vertex {
+    area: f64,
+    idx: usize,
+}
+
+impl PartialEq for Vertex {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Vertex {}
+
+impl PartialOrd for Vertex {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Vertex {
+    // reversed for min-heap
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .area
+            .partial_cmp(&self.area)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| other.idx.cmp(&self.idx))
+    }
+}
+
+fn triangle_area(a: &Px, b: &Px, c: &Px) -> f64 {
+    ((a.x - c.x) as f64 * (b.y - a.y) as f64 - (a.x - b.x) as f64 * (c.y - a.y) as f64).abs() / 2.0
+}
+
+/// Visvalingam–Whyatt line simplification
+/// https://en.wikipedia.org/wiki/Visvalingam%E2%80%93Whyatt_algorithm
+pub fn visvalingam(points: &[Px], min_area: f64) -> Vec<Px> {
+    let n = points.len();
+    if n < 3 {
+        return points.to_vec();
+    }
+    // linked list of remaining points
+    let mut prev: Vec<usize> = (0..n).map(|i| i.saturating_sub(1)).collect();
+    let mut next: Vec<usize> = (0..n).map(|i| (i + 1).min(n - 1)).collect();
+    let mut area = vec![std::f64::INFINITY; n];
+    let mut heap = BinaryHeap::with_capacity(n);
+    for i in 1..n - 1 {
+        area[i] = triangle_area(&points[i - 1], &points[i], &points[i + 1]);
+        heap.push(Vertex {
+            area: area[i],
+            idx: i,
+        });
+    }
+
+    let mut removed = vec![false; n];
+    while let Some(Vertex { area: a, idx }) = heap.pop() {
+        // skip stale entries
+        if removed[idx] || a != area[idx] {
+            continue;
+        }
+        if a >= min_area {
+            break;
+        }
+        removed[idx] = true;
+        let (p, q) = (prev[idx], next[idx]);
+        next[p] = q;
+        prev[q] = p;
+        // recalculate neighbours, area never decreases to keep removal order stable
+        for &j in &[p, q] {
+            if j != 0 && j != n - 1 {
+                area[j] = triangle_area(&points[prev[j]], &points[j], &points[next[j]]).max(a);
+                heap.push(Vertex {
+                    area: area[j],
+                    idx: j,
+                });
+            }
+        }
+    }
+
+    points
+        .iter()
+        .zip(removed)
+        .filter_map(|(p, r)| if r { None } else { Some(*p) })
+        .collect()
+}
@@ -0,0 +1,164 @@
+use super::expr::{Expr, Kind};
+use failure::Fail;
+use serde::de::{self, Deserialize, Deserializer};
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// Vector feature drawing style
+#[derive(Debug, PartialEq, Clone)]
+pub struct Style {
+    /// Line color
+    pub stroke: String,
+    /// Line width in pixels
+    pub stroke_width: f64,
+    pub stroke_opacity: f64,
+    /// Line dash pattern in pixels, solid line if empty
+    pub dash: Vec<f64>,
+    /// Polygon and point fill color
+    pub fill: String,
+    pub fill_opacity: f64,
+    /// Point radius in pixels
+    pub radius: f64,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            stroke: "#3388ff".into(),
+            stroke_width: 3.0,
+            stroke_opacity: 1.0,
+            dash: vec![],
+            fill: "#3388ff".into(),
+            fill_opacity: 0.2,
+            radius: 5.0,
+        }
+    }
+}
+
+/// Data driven style, properties override base style
+///
+/// Loaded from json object with properties `stroke`, `stroke-width`, `stroke-opacity`,
+/// `dash`, `fill`, `fill-opacity` and `radius`, each a constant or an expression.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct StyleSpec {
+    pub stroke: Option<Expr>,
+    pub stroke_width: Option<Expr>,
+    pub stroke_opacity: Option<Expr>,
+    pub dash: Option<Expr>,
+    pub fill: Option<Expr>,
+    pub fill_opacity: Option<Expr>,
+    pub radius: Option<Expr>,
+}
+
+/// Style spec loading error
+#[derive(Debug, PartialEq, Clone)]
+pub enum StyleError {
+    /// Malformed json
+    Json(String),
+    /// Spec is not a json object
+    NotObject,
+    /// Unknown style property
+    Unknown(String),
+    /// Invalid expression, property path and reason
+    Invalid(String, String),
+}
+
+impl fmt::Display for StyleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StyleError::Json(e) => write!(f, "invalid style json: {}", e),
+            StyleError::NotObject => write!(f, "style must be a json object"),
+            StyleError::Unknown(p) => write!(
+                f,
+                "unknown style property '{}', expected one of {}",
+                p,
+                PROPERTIES.join(", ")
+            ),
+            StyleError::Invalid(path, msg) => write!(f, "invalid style at {}: {}", path, msg),
+        }
+    }
+}
+
+impl Fail for StyleError {}
+
+// style properties in json
+const PROPERTIES: [&str; 7] = [
+    "stroke",
+    "stroke-width",
+    "stroke-opacity",
+    "dash",
+    "fill",
+    "fill-opacity",
+    "radius",
+];
+
+impl StyleSpec {
+    pub fn from_json(json: &str) -> Result<Self, StyleError> {
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| StyleError::Json(e.to_string()))?;
+        Self::from_value(&value)
+    }
+
+    /// Parses and validates spec from json object
+    pub fn from_value(value: &Value) -> Result<Self, StyleError> {
+        let object = value.as_object().ok_or(StyleError::NotObject)?;
+        let mut spec = StyleSpec::default();
+        for (key, value) in object {
+            let (field, kind) = match key.as_str() {
+                "stroke" => (&mut spec.stroke, Kind::Color),
+                "stroke-width" => (&mut spec.stroke_width, Kind::Number),
+                "stroke-opacity" => (&mut spec.stroke_opacity, Kind::Number),
+                "dash" => (&mut spec.dash, Kind::Numbers),
+                "fill" => (&mut spec.fill, Kind::Color),
+                "fill-opacity" => (&mut spec.fill_opacity, Kind::Number),
+                "radius" => (&mut spec.radius, Kind::Number),
+                _ => return Err(StyleError::Unknown(key.clone())),
+            };
+            *field = Some(Expr::parse(value, kind, key)?);
+        }
+        Ok(spec)
+    }
+
+    /// Resolves style of feature with given properties on zoom level
+    ///
+    /// Properties evaluating to a value of wrong type keep the base value.
+    pub fn resolve(
+        &self,
+        base: &Style,
+        zoom: f64,
+        properties: Option<&Map<String, Value>>,
+    ) -> Style {
+        let eval = |e: &Option<Expr>| e.as_ref().map(|e| e.eval(zoom, properties));
+        let color = |e: &Option<Expr>, base: &String| match eval(e) {
+            Some(Value::String(s)) => s,
+            _ => base.clone(),
+        };
+        let number = |e: &Option<Expr>, base: f64| eval(e).and_then(|v| v.as_f64()).unwrap_or(base);
+        let dash = match eval(&self.dash) {
+            Some(Value::Array(a)) if a.iter().all(Value::is_number) => {
+                a.iter().filter_map(Value::as_f64).collect()
+            }
+            _ => base.dash.clone(),
+        };
+        Style {
+            stroke: color(&self.stroke, &base.stroke),
+            stroke_width: number(&self.stroke_width, base.stroke_width).max(0.0),
+            stroke_opacity: number(&self.stroke_opacity, base.stroke_opacity)
+                .max(0.0)
+                .min(1.0),
+            dash,
+            fill: color(&self.fill, &base.fill),
+            fill_opacity: number(&self.fill_opacity, base.fill_opacity)
+                .max(0.0)
+                .min(1.0),
+            radius: number(&self.radius, base.radius).max(0.0),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for StyleSpec {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        Self::from_value(&value).map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,152 @@
+use failure::Fail;
+use std::fmt;
+
+/// Placeholders resolved per tile
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Placeholder {
+    /// `{z}`
+    Z,
+    /// `{x}`
+    X,
+    /// `{y}`
+    Y,
+    /// `{-y}`, y counted from south regardless of layer scheme
+    FlippedY,
+    /// `{q}`, quadkey
+    Quadkey,
+    /// `{s}`, subdomain
+    Subdomain,
+    /// `{r}`, retina suffix
+    Retina,
+}
+
+impl Placeholder {
+    fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "z" => Some(Placeholder::Z),
+            "x" => Some(Placeholder::X),
+            "y" => Some(Placeholder::Y),
+            "-y" => Some(Placeholder::FlippedY),
+            "q" => Some(Placeholder::Quadkey),
+            "s" => Some(Placeholder::Subdomain),
+            "r" => Some(Placeholder::Retina),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Part {
+    Text(String),
+    Placeholder(Placeholder),
+}
+
+/// Error from parsing url template
+#[derive(Debug, PartialEq)]
+pub enum TemplateError {
+    /// Opening brace without closing one, at position
+    Unclosed(usize, String),
+    /// Closing brace without opening one, at position
+    UnexpectedClose(usize, String),
+    /// Placeholder without key, at position
+    Empty(usize, String),
+    /// Placeholder key with no known or given value
+    Unknown(String, String),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TemplateError::Unclosed(i, t) => {
+                write!(f, "unclosed '{{' at position {} in url template '{}'", i, t)
+            }
+            TemplateError::UnexpectedClose(i, t) => {
+                write!(
+                    f,
+                    "unexpected '}}' at position {} in url template '{}'",
+                    i, t
+                )
+            }
+            TemplateError::Empty(i, t) => {
+                write!(
+                    f,
+                    "empty placeholder at position {} in url template '{}'",
+                    i, t
+                )
+            }
+            TemplateError::Unknown(k, t) => write!(
+                f,
+                "unknown placeholder '{{{}}}' in url template '{}', no value given for it",
+                k, t
+            ),
+        }
+    }
+}
+
+impl Fail for TemplateError {}
+
+/// Tile url template, eg. 'https://{s}.tile.example.org/{z}/{x}/{y}{r}.png'
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct UrlTemplate {
+    parts: Vec<Part>,
+}
+
+impl UrlTemplate {
+    /// Parses template, substituting custom placeholders with given key-value pairs
+    pub fn parse(template: &str, values: &[(&str, &str)]) -> Result<Self, TemplateError> {
+        let mut parts = vec![];
+        let mut text = String::new();
+        let mut chars = template.char_indices();
+
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '{' => {
+                    // read key until closing brace
+                    let mut key = String::new();
+                    loop {
+                        match chars.next() {
+                            Some((_, '}')) => break,
+                            Some((_, c)) => key.push(c),
+                            None => return Err(TemplateError::Unclosed(i, template.into())),
+                        }
+                    }
+                    if key.is_empty() {
+                        return Err(TemplateError::Empty(i, template.into()));
+                    }
+                    // custom values take precedence over builtin placeholders
+                    if let Some((_, value)) = values.iter().find(|(k, _)| *k == key) {
+                        text.push_str(value);
+                    } else if let Some(p) = Placeholder::from_key(&key) {
+                        if !text.is_empty() {
+                            parts.push(Part::Text(text.split_off(0)));
+                        }
+                        parts.push(Part::Placeholder(p));
+                    } else {
+                        return Err(TemplateError::Unknown(key, template.into()));
+                    }
+                }
+                '}' => return Err(TemplateError::UnexpectedClose(i, template.into())),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+
+        Ok(Self { parts })
+    }
+
+    /// Expands template, resolving placeholders with given function
+    pub fn expand<F>(&self, resolve: F) -> String
+    where
+        F: Fn(Placeholder) -> String,
+    {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Text(text) => text.clone(),
+                Part::Placeholder(p) => resolve(*p),
+            })
+            .collect()
+    }
+}
@@ -1,7 +1,9 @@
+use super::position::EARTH_RADIUS;
+use super::template::{Placeholder, TemplateError, UrlTemplate};
 use super::{LonLat, Px, Viewport};
 use std::f64::consts::PI;
 
-#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Tile {
     pub x: u32,
     pub y: u32,
@@ -60,35 +62,305 @@ impl Tile {
         let ll: LonLat = self.nw().into();
         ll.px(self.z)
     }
+
+    /// Returns pixel coordinates (NW corner) of this tile on zoom level `z`
+    pub fn pixels_at(&self, z: usize) -> Px {
+        let scale = 2f64.powi(z as i32 - self.z as i32);
+        (self.x as f64 * 256.0 * scale, self.y as f64 * 256.0 * scale).into()
+    }
+
+    /// Returns drawn tile size in pixels on zoom level `z`
+    pub fn size_at(&self, z: usize) -> i64 {
+        (256.0 * 2f64.powi(z as i32 - self.z as i32)) as i64
+    }
+
+    /// Returns y index counted from south, as used by TMS
+    pub fn tms_y(&self) -> u32 {
+        (1 << self.z) - 1 - self.y
+    }
+
+    /// Returns Bing-style quadkey of this tile
+    /// https://docs.microsoft.com/en-us/bingmaps/articles/bing-maps-tile-system
+    pub fn quadkey(&self) -> String {
+        (1..=self.z)
+            .rev()
+            .map(|i| {
+                let mask = 1 << (i - 1);
+                let mut digit = 0;
+                if self.x & mask != 0 {
+                    digit += 1;
+                }
+                if self.y & mask != 0 {
+                    digit += 2;
+                }
+                std::char::from_digit(digit, 10).unwrap_or('0')
+            })
+            .collect()
+    }
+}
+
+/// Tile fetching mode on high-DPI screens
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Retina {
+    /// Fetch standard tiles
+    Off,
+    /// Fetch '@2x' tiles of same zoom level
+    Suffix,
+    /// Fetch tiles from one zoom level higher
+    Zoom,
+}
+
+impl Default for Retina {
+    fn default() -> Self {
+        Retina::Off
+    }
+}
+
+/// WMTS key-value-pair request parameters
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Wmts {
+    pub layer: String,
+    pub style: String,
+    /// Image mime type eg. 'image/png'
+    pub format: String,
+    pub tile_matrix_set: String,
+    /// Prefix for tile matrix identifiers, eg. 'EPSG:900913:'
+    pub tile_matrix_prefix: String,
+}
+
+/// Coordinate reference system of WMS requests
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Crs {
+    /// Spherical mercator, bbox in meters
+    Epsg3857,
+    /// WGS84, bbox in degrees
+    Epsg4326,
+}
+
+impl Default for Crs {
+    fn default() -> Self {
+        Crs::Epsg3857
+    }
+}
+
+/// WMS GetMap request parameters
+#[derive(Debug, PartialEq, Clone)]
+pub struct Wms {
+    /// Comma-separated layer names
+    pub layers: String,
+    /// Comma-separated style names, may be empty
+    pub styles: String,
+    /// Image mime type eg. 'image/png'
+    pub format: String,
+    pub transparent: bool,
+    /// '1.1.1' or '1.3.0', versions from 1.3 on use the 1.3.0 parameters
+    pub version: String,
+    pub crs: Crs,
+}
+
+impl Default for Wms {
+    fn default() -> Self {
+        Self {
+            layers: String::new(),
+            styles: String::new(),
+            format: "image/png".into(),
+            transparent: true,
+            version: "1.3.0".into(),
+            crs: Crs::Epsg3857,
+        }
+    }
+}
+
+impl Wms {
+    // checks if version is 1.3 or later, which changed crs key and axis order
+    fn is_1_3(&self) -> bool {
+        let mut parts = self
+            .version
+            .split('.')
+            .map(|p| p.trim().parse::<u32>().unwrap_or(0));
+        let major = parts.next().unwrap_or(0);
+        let minor = parts.next().unwrap_or(0);
+        (major, minor) >= (1, 3)
+    }
+    // returns bbox parameter for tile in layer projection
+    fn bbox(&self, tile: &Tile) -> String {
+        let bbox = tile.bbox();
+        match self.crs {
+            Crs::Epsg3857 => {
+                // spherical mercator world is a square centered at origin
+                let size = 2.0 * PI * EARTH_RADIUS / 2f64.powi(tile.z as i32);
+                let x_min = -PI * EARTH_RADIUS + tile.x as f64 * size;
+                let y_max = PI * EARTH_RADIUS - tile.y as f64 * size;
+                format!("{},{},{},{}", x_min, y_max - size, x_min + size, y_max)
+            }
+            // axis order is lat,lon since wms 1.3.0
+            Crs::Epsg4326 if self.is_1_3() => format!(
+                "{},{},{},{}",
+                bbox.lat_min, bbox.lon_min, bbox.lat_max, bbox.lon_max
+            ),
+            Crs::Epsg4326 => format!(
+                "{},{},{},{}",
+                bbox.lon_min, bbox.lat_min, bbox.lon_max, bbox.lat_max
+            ),
+        }
+    }
+}
+
+// percent-encodes query parameter value, keeping characters common in layer names
+fn encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for b in value.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' => out.push(b as char),
+            b'-' | b'.' | b'_' | b'~' | b':' | b'/' | b',' => out.push(b as char),
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+/// Tile addressing scheme
+///
+/// Quadkeys and flipped rows need no scheme, they are picked by the
+/// '{q}' and '{-y}' template placeholders.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Scheme {
+    /// Tiles addressed by template placeholders, eg. slippy map '{z}/{x}/{y}'
+    Xyz,
+    /// Like XYZ, with '{y}' counted from south.
+    /// '{-y}' is the same row, it is not flipped twice.
+    Tms,
+    /// WMTS GetTile request with key-value-pair encoding,
+    /// appended to the expanded url template
+    Wmts(Wmts),
+    /// WMS GetMap request with tile bounding box,
+    /// appended to the expanded url template
+    Wms(Wms),
+}
+
+impl Default for Scheme {
+    fn default() -> Self {
+        Scheme::Xyz
+    }
 }
 
 /// Raster tile layer
 #[derive(Default, PartialEq, Clone)]
 pub struct TileLayer {
-    /// Tile url
-    pub url: String,
-    /// Tile url suffix eg. '.png?apikey=foobar'
-    pub suffix: String,
+    /// Tile url template
+    pub template: UrlTemplate,
+    /// Name shown in layer switcher
+    pub name: String,
+    /// Overlays are toggled independently, only one base layer is visible at a time
+    pub overlay: bool,
+    /// Credit shown in attribution control, inserted as raw html
+    pub attribution: Option<String>,
+    /// Subdomains rotated in place of '{s}'
+    pub subdomains: Vec<String>,
     /// Controls whether layer is visible on map
     pub visible: bool,
+    /// Highest zoom level served by the tile source.
+    /// Above this, tiles from this level are scaled up.
+    pub max_native_zoom: usize,
+    /// Tile image size in pixels, 256 or 512
+    pub tile_size: u32,
+    /// Tile fetching mode on high-DPI screens
+    pub retina: Retina,
+    /// Tile addressing scheme
+    pub scheme: Scheme,
 }
 
 impl TileLayer {
-    pub fn new(url: &str, suffix: &str) -> Self {
-        Self {
-            url: url.into(),
-            suffix: suffix.into(),
+    /// Creates layer from url template,
+    /// eg. 'https://{s}.tile.example.org/{z}/{x}/{y}{r}.png'
+    pub fn new(template: &str) -> Result<Self, TemplateError> {
+        Self::with_values(template, &[])
+    }
+    /// Creates layer from url template with values for custom placeholders,
+    /// eg. 'https://tile.example.org/{z}/{x}/{y}.png?apikey={apikey}'
+    pub fn with_values(template: &str, values: &[(&str, &str)]) -> Result<Self, TemplateError> {
+        Ok(Self {
+            template: UrlTemplate::parse(template, values)?,
+            name: String::new(),
+            overlay: false,
+            attribution: None,
+            subdomains: vec!["a".into(), "b".into(), "c".into()],
             visible: true,
+            max_native_zoom: 18,
+            tile_size: 256,
+            retina: Retina::Off,
+            scheme: Scheme::Xyz,
+        })
+    }
+    /// Returns zoom level of tiles to fetch when map is on zoom level `z`
+    pub fn source_zoom(&self, z: usize, hidpi: bool) -> usize {
+        // larger tiles cover the area of multiple 256px tiles
+        let offset = (self.tile_size / 256).max(1).trailing_zeros() as usize;
+        let z = match self.retina {
+            Retina::Zoom if hidpi => z + 1,
+            _ => z,
+        };
+        z.saturating_sub(offset).min(self.max_native_zoom)
+    }
+    /// Returns subdomain for tile, rotating between available ones
+    fn subdomain(&self, tile: &Tile) -> &str {
+        match self.subdomains.len() {
+            0 => "",
+            n => &self.subdomains[(tile.x as usize + tile.y as usize) % n],
         }
     }
-    pub fn tile_url(&self, tile: &Tile) -> String {
-        format!(
-            "{url}/{z}/{x}/{y}{suffix}",
-            url = &self.url,
-            z = tile.z,
-            x = tile.x,
-            y = tile.y,
-            suffix = &self.suffix,
-        )
+    pub fn tile_url(&self, tile: &Tile, hidpi: bool) -> String {
+        let url = self.template.expand(|p| match p {
+            Placeholder::Z => tile.z.to_string(),
+            Placeholder::X => tile.x.to_string(),
+            Placeholder::Y if self.scheme == Scheme::Tms => tile.tms_y().to_string(),
+            Placeholder::Y => tile.y.to_string(),
+            Placeholder::FlippedY => tile.tms_y().to_string(),
+            Placeholder::Quadkey => tile.quadkey(),
+            Placeholder::Subdomain => self.subdomain(tile).into(),
+            Placeholder::Retina if hidpi && self.retina == Retina::Suffix => "@2x".into(),
+            Placeholder::Retina => "".into(),
+        });
+        match self.scheme {
+            Scheme::Wmts(ref wmts) => format!(
+                "{url}{sep}SERVICE=WMTS&REQUEST=GetTile&VERSION=1.0.0\
+                 &LAYER={layer}&STYLE={style}&FORMAT={format}&TILEMATRIXSET={set}\
+                 &TILEMATRIX={prefix}{z}&TILEROW={y}&TILECOL={x}",
+                sep = if url.contains('?') { '&' } else { '?' },
+                url = url,
+                layer = encode(&wmts.layer),
+                style = encode(&wmts.style),
+                format = encode(&wmts.format),
+                set = encode(&wmts.tile_matrix_set),
+                prefix = encode(&wmts.tile_matrix_prefix),
+                z = tile.z,
+                x = tile.x,
+                y = tile.y,
+            ),
+            Scheme::Wms(ref wms) => format!(
+                "{url}{sep}SERVICE=WMS&REQUEST=GetMap&VERSION={version}\
+                 &LAYERS={layers}&STYLES={styles}&FORMAT={format}&TRANSPARENT={transparent}\
+                 &{crs_key}={crs}&BBOX={bbox}&WIDTH={size}&HEIGHT={size}",
+                sep = if url.contains('?') { '&' } else { '?' },
+                url = url,
+                version = &wms.version,
+                layers = encode(&wms.layers),
+                styles = encode(&wms.styles),
+                format = encode(&wms.format),
+                transparent = if wms.transparent { "TRUE" } else { "FALSE" },
+                // crs parameter was called srs before wms 1.3.0
+                crs_key = if wms.is_1_3() { "CRS" } else { "SRS" },
+                crs = match wms.crs {
+                    Crs::Epsg3857 => "EPSG:3857",
+                    Crs::Epsg4326 => "EPSG:4326",
+                },
+                bbox = wms.bbox(tile),
+                size = match self.retina {
+                    Retina::Suffix if hidpi => self.tile_size * 2,
+                    _ => self.tile_size,
+                },
+            ),
+            _ => url,
+        }
     }
 }
@@ -61,10 +61,19 @@ impl Viewport {
         nw_px.avg(&se.px(self.z)).lonlat(self.z)
     }
 
+    /// Returns viewport size in pixels
+    pub fn size(&self) -> Px {
+        self.point_offset(&(self.lon_max, self.lat_min).into())
+    }
+
     /// Returns osm tiles that intersect with this viewport
     /// https://wiki.openstreetmap.org/wiki/Slippy_map_tilenames#Mathematics
     pub fn tiles(&self) -> impl Iterator<Item = Tile> {
-        let z = self.z;
+        self.tiles_at(self.z)
+    }
+
+    /// Returns osm tiles on zoom level `z` that intersect with this viewport
+    pub fn tiles_at(&self, z: usize) -> impl Iterator<Item = Tile> {
         let a = Tile::from_lonlat(self.lon_min, self.lat_max, z);
         let b = Tile::from_lonlat(self.lon_max, self.lat_min, z).add(1);
 
@@ -74,7 +83,12 @@ impl Viewport {
 
     /// Calculates pixel offset for tile
     pub fn pixel_offset(&self, tile: &Tile) -> Px {
-        tile.pixels().distance(&self.pixels())
+        tile.pixels_at(self.z).distance(&self.pixels())
+    }
+
+    /// Calculates pixel offset for point
+    pub fn point_offset(&self, ll: &LonLat) -> Px {
+        ll.px(self.z).distance(&self.pixels())
     }
 
     /// Get pixel coordinates (NW corner)
@@ -90,6 +104,17 @@ impl Viewport {
         (nw.px(self.z), se.px(self.z))
     }
 
+    /// Returns highest zoom level up to `max` where nw, se corners fit in pixel size
+    pub fn fit_zoom(nw: &LonLat, se: &LonLat, size_px: (i32, i32), max: usize) -> usize {
+        (0..=max)
+            .rev()
+            .find(|&z| {
+                let d = se.px(z).distance(&nw.px(z));
+                d.x <= i64::from(size_px.0) && d.y <= i64::from(size_px.1)
+            })
+            .unwrap_or(0)
+    }
+
     /// Creates viewport from pixel bounds and zoom level
     pub fn from_pixel_bounds(nw: Px, se: Px, z: usize) -> Self {
         let nw_ll = nw.lonlat(z);
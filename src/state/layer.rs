@@ -19,9 +19,31 @@ impl State {
         }
     }
 
+    /// Shows base layer by index and hides other base layers,
+    /// returns indices of layers with changed visibility
+    pub fn select_base(&mut self, idx: usize) -> Vec<usize> {
+        let mut changed = vec![];
+        match self.layers.get(idx) {
+            Some(layer) if !layer.overlay => (),
+            _ => return changed,
+        }
+        for (i, layer) in self.layers.iter_mut().enumerate() {
+            if !layer.overlay && layer.visible != (i == idx) {
+                layer.visible = i == idx;
+                changed.push(i);
+            }
+        }
+        changed
+    }
+
+    /// Returns layer by index
+    pub fn get(&self, idx: usize) -> Option<&TileLayer> {
+        self.layers.get(idx)
+    }
+
     /// Returns all held layers
-    pub fn layers(&self) -> Vec<TileLayer> {
-        self.layers.clone()
+    pub fn layers(&self) -> &[TileLayer] {
+        &self.layers
     }
 
     /// Returns layer list filtered by visibility status
@@ -33,3 +55,29 @@ impl State {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_base_1() {
+        let layer = |overlay: bool, visible: bool| TileLayer {
+            overlay,
+            visible,
+            ..TileLayer::new("https://tile.example.org/{z}/{x}/{y}.png").unwrap()
+        };
+        let mut state = State::new(vec![
+            layer(false, true),
+            layer(false, false),
+            layer(true, true),
+        ]);
+        assert_eq!(state.select_base(1), vec![0, 1]);
+        assert_eq!(state.select_base(1), Vec::<usize>::new());
+        // overlays are not base layers
+        assert_eq!(state.select_base(2), Vec::<usize>::new());
+        assert_eq!(state.select_base(3), Vec::<usize>::new());
+        let visible: Vec<bool> = state.layers().iter().map(|l| l.visible).collect();
+        assert_eq!(visible, vec![false, true, true]);
+    }
+}
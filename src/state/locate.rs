@@ -0,0 +1,145 @@
+use crate::model::{GeolocationError, Location, LonLat};
+
+/// State of locating the user
+#[derive(Default, PartialEq, Clone)]
+pub struct State {
+    pub status: Status,
+    /// keep map centered on position updates
+    follow: bool,
+    /// last known location
+    location: Option<Location>,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Status {
+    Off,
+    /// waiting for first position
+    Locating,
+    Found,
+}
+
+impl Default for Status {
+    fn default() -> Self {
+        Status::Off
+    }
+}
+
+/// Outcome of a provider result
+#[derive(Debug, PartialEq, Clone)]
+pub enum Update {
+    /// Result arrived after locating stopped
+    Ignored,
+    /// Location found, with position to center map on
+    Found(Location, Option<LonLat>),
+    /// Locating failed, it is stopped on permanent errors only
+    Failed(GeolocationError),
+}
+
+impl State {
+    /// Starts locating, previous location is forgotten
+    pub fn start(&mut self, follow: bool) {
+        self.status = Status::Locating;
+        self.follow = follow;
+        self.location = None;
+    }
+    /// Stops locating and hides location
+    pub fn stop(&mut self) {
+        *self = Self::default();
+    }
+    pub fn active(&self) -> bool {
+        self.status != Status::Off
+    }
+    /// Returns last known location while active
+    pub fn location(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+    /// Handles provider result.
+    /// Found locations are centered on first fix always and later ones when following.
+    pub fn receive(&mut self, result: Result<Location, GeolocationError>) -> Update {
+        if !self.active() {
+            return Update::Ignored;
+        }
+        match result {
+            Ok(location) => {
+                let first = self.status == Status::Locating;
+                self.status = Status::Found;
+                self.location = Some(location);
+                let center = if first || self.follow {
+                    Some(location.position)
+                } else {
+                    None
+                };
+                Update::Found(location, center)
+            }
+            Err(e) => {
+                // transient errors keep watching, later positions may still arrive
+                if e.is_permanent() {
+                    self.stop();
+                }
+                Update::Failed(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fix(lon: f64) -> Location {
+        Location {
+            position: LonLat { lon, lat: 0.0 },
+            accuracy: 10.0,
+        }
+    }
+
+    #[test]
+    fn test_receive_1() {
+        let ll = |lon: f64| LonLat { lon, lat: 0.0 };
+        // first fix centers map
+        let mut state = State::default();
+        state.start(false);
+        assert_eq!(
+            state.receive(Ok(fix(10.0))),
+            Update::Found(fix(10.0), Some(ll(10.0)))
+        );
+        assert_eq!(state.receive(Ok(fix(11.0))), Update::Found(fix(11.0), None));
+        assert_eq!(state.status, Status::Found);
+        // transient errors keep last location
+        assert_eq!(
+            state.receive(Err(GeolocationError::Timeout)),
+            Update::Failed(GeolocationError::Timeout)
+        );
+        assert!(state.active());
+        assert_eq!(state.location(), Some(&fix(11.0)));
+        // following centers on every fix
+        state.start(true);
+        assert_eq!(state.location(), None);
+        state.receive(Ok(fix(10.0)));
+        assert_eq!(
+            state.receive(Ok(fix(11.0))),
+            Update::Found(fix(11.0), Some(ll(11.0)))
+        );
+    }
+
+    #[test]
+    fn test_receive_2() {
+        // permanent errors stop locating
+        let mut state = State::default();
+        state.start(true);
+        state.receive(Ok(fix(10.0)));
+        assert_eq!(
+            state.receive(Err(GeolocationError::PermissionDenied)),
+            Update::Failed(GeolocationError::PermissionDenied)
+        );
+        assert!(!state.active());
+        assert_eq!(state.location(), None);
+        // results after stop are ignored
+        assert_eq!(state.receive(Ok(fix(1.0))), Update::Ignored);
+        assert_eq!(
+            state.receive(Err(GeolocationError::Timeout)),
+            Update::Ignored
+        );
+        assert_eq!(state.location(), None);
+    }
+}
@@ -1,3 +1,5 @@
 pub mod inertia;
 pub mod layer;
+pub mod locate;
 pub mod panning;
+pub mod press;
@@ -0,0 +1,66 @@
+/// State of a pointer press, tells clicks apart from the end of a drag.
+/// Browsers send a click after mouseup also when the map was panned in between.
+#[derive(Default, PartialEq, Clone, Copy)]
+pub struct State {
+    // pressed pointer moved the map
+    moved: bool,
+}
+
+impl State {
+    /// Begins press, forgetting earlier movement
+    pub fn begin(&mut self) {
+        self.moved = false;
+    }
+    /// Records panning offset from press origin
+    pub fn pan(&mut self, offset: (f64, f64)) {
+        if offset != (0.0, 0.0) {
+            self.moved = true;
+        }
+    }
+    /// Returns true if click ending the press is a click and not the end of a drag
+    pub fn is_click(&self) -> bool {
+        !self.moved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_click_1() {
+        let mut press = State::default();
+        press.begin();
+        press.pan((0.0, 0.0));
+        assert!(press.is_click());
+        // clicks after a drag are dropped
+        press.pan((12.0, -3.0));
+        press.pan((0.0, 0.0));
+        assert!(!press.is_click());
+        // next press is a click again
+        press.begin();
+        assert!(press.is_click());
+    }
+
+    #[test]
+    fn test_click_2() {
+        use crate::model::{LonLat, MeasureKind, Measurement};
+
+        let ll = |lon: f64| LonLat { lon, lat: 0.0 };
+        let mut m = Measurement::new(MeasureKind::Distance);
+        let mut press = State::default();
+        // press, optional pan and release followed by browser click
+        let mut input = |m: &mut Measurement, offset: (f64, f64), at: LonLat| {
+            press.begin();
+            press.pan(offset);
+            if press.is_click() {
+                m.click(at);
+            }
+        };
+        input(&mut m, (0.0, 0.0), ll(1.0));
+        // panning while measuring adds no vertex where the pointer is released
+        input(&mut m, (40.0, 10.0), ll(2.0));
+        input(&mut m, (0.0, 0.0), ll(3.0));
+        assert_eq!(m.vertices, vec![ll(1.0), ll(3.0)]);
+    }
+}